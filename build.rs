@@ -0,0 +1,16 @@
+use std::{env, path::PathBuf};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // protoc vendorizado: compilar não exige protoc instalado na máquina
+    env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    
+    let out_dir = PathBuf::from(env::var("OUT_DIR")?);
+    
+    tonic_prost_build::configure()
+        .build_client(false)
+        .build_transport(false)
+        .file_descriptor_set_path(out_dir.join("echo_descriptor.bin"))
+        .compile_protos(&["proto/echo.proto"], &["proto"])?;
+    
+    Ok(())
+}
@@ -0,0 +1,378 @@
+use axum::{
+    extract::{Request, State},
+    http::{HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use rand::Rng;
+use serde_json::json;
+use std::{sync::Arc, time::Duration};
+
+use crate::config::env_flag;
+use crate::{AppState, MAX_DELAY};
+
+// Headers de controle lidos pela camada de caos
+const DELAY_HEADER: &str = "x-rustjin-delay";
+const JITTER_HEADER: &str = "x-rustjin-jitter";
+const STATUS_HEADER: &str = "x-rustjin-status";
+const FAIL_RATE_HEADER: &str = "x-rustjin-fail-rate";
+const DROP_RATE_HEADER: &str = "x-rustjin-drop-rate";
+
+// Resumo do que foi aplicado, devolvido na resposta
+const APPLIED_HEADER: &str = "x-rustjin-chaos";
+
+// Nome nas métricas das respostas geradas pelo caos: o caminho cru criaria uma chave por URL
+const METRICS_ENDPOINT: &str = "chaos";
+
+// Camada de caos aplicada a qualquer rota; desligada por padrão
+#[derive(Clone)]
+pub struct ChaosConfig {
+    pub enabled: bool,
+}
+
+impl ChaosConfig {
+    pub fn from_env() -> Self {
+        Self {
+            enabled: env_flag("RUSTJIN_CHAOS"),
+        }
+    }
+}
+
+// Marca a resposta para que a conexão (ou o stream, em HTTP/2 e HTTP/3) seja derrubada
+#[derive(Clone, Copy)]
+pub struct DropConnection;
+
+// Distribuições de latência, em milissegundos
+enum Latency {
+    Fixed(f64),
+    Uniform(f64, f64),
+    Normal { mean: f64, std_dev: f64 },
+    Pareto { scale: f64, shape: f64 },
+}
+
+impl Latency {
+    // "250", "fixed:250", "uniform:100-500", "normal:300,50" ou "pareto:100,1.5"
+    fn parse(spec: &str) -> Result<Self, String> {
+        let (kind, params) = spec.split_once(':').unwrap_or(("fixed", spec));
+        let numbers: Vec<f64> = params
+            .split([',', '-'])
+            .map(|n| n.trim().parse::<f64>())
+            .collect::<Result<_, _>>()
+            .map_err(|_| format!("Invalid numbers in '{}'", params))?;
+        
+        let latency = match (kind.trim().to_lowercase().as_str(), numbers.as_slice()) {
+            ("fixed", [ms]) => Self::Fixed(*ms),
+            ("uniform", [min, max]) if min <= max => Self::Uniform(*min, *max),
+            ("normal", [mean, std_dev]) if *std_dev >= 0.0 => Self::Normal {
+                mean: *mean,
+                std_dev: *std_dev,
+            },
+            ("pareto", [scale, shape]) if *scale > 0.0 && *shape > 0.0 => Self::Pareto {
+                scale: *scale,
+                shape: *shape,
+            },
+            _ => {
+                return Err(
+                    "Expected fixed:<ms>, uniform:<min>-<max>, normal:<mean>,<std_dev> or pareto:<scale>,<shape>"
+                        .to_string(),
+                )
+            }
+        };
+        
+        if numbers.iter().any(|n| !n.is_finite() || *n < 0.0) {
+            return Err("Values must be non-negative milliseconds".to_string());
+        }
+        Ok(latency)
+    }
+
+    // Valor que a distribuição pede explicitamente (o sorteio pode passar disso e é limitado)
+    fn nominal(&self) -> f64 {
+        match self {
+            Self::Fixed(ms) => *ms,
+            Self::Uniform(_, max) => *max,
+            Self::Normal { mean, .. } => *mean,
+            Self::Pareto { scale, .. } => *scale,
+        }
+    }
+
+    fn sample(&self, rng: &mut impl Rng) -> f64 {
+        match self {
+            Self::Fixed(ms) => *ms,
+            Self::Uniform(min, max) => rng.gen_range(*min..=*max),
+            Self::Normal { mean, std_dev } => {
+                // Box-Muller
+                let u1: f64 = 1.0 - rng.gen::<f64>();
+                let u2: f64 = rng.gen();
+                let z = (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos();
+                (mean + std_dev * z).max(0.0)
+            }
+            // Inversa da CDF: scale / U^(1/shape)
+            Self::Pareto { scale, shape } => scale / (1.0 - rng.gen::<f64>()).powf(1.0 / shape),
+        }
+    }
+}
+
+// Efeitos pedidos pelos headers de uma requisição
+struct Chaos {
+    latency: Option<Latency>,
+    jitter: f64,
+    statuses: Vec<StatusCode>,
+    fail_rate: f64,
+    drop_rate: f64,
+}
+
+impl Chaos {
+    fn from_headers(headers: &HeaderMap) -> Result<Option<Self>, (&'static str, String)> {
+        let get = |name| headers.get(name).and_then(|v| v.to_str().ok()).map(str::trim);
+        let rate = |name: &'static str| -> Result<Option<f64>, (&'static str, String)> {
+            get(name)
+                .map(|v| match v.parse::<f64>() {
+                    Ok(rate) if (0.0..=1.0).contains(&rate) => Ok(rate),
+                    _ => Err((name, "Expected a probability between 0 and 1".to_string())),
+                })
+                .transpose()
+        };
+        
+        let latency = get(DELAY_HEADER)
+            .map(Latency::parse)
+            .transpose()
+            .map_err(|e| (DELAY_HEADER, e))?;
+        let jitter = get(JITTER_HEADER)
+            .map(|v| match v.parse::<f64>() {
+                Ok(ms) if ms.is_finite() && ms >= 0.0 => Ok(ms),
+                _ => Err((JITTER_HEADER, "Expected non-negative milliseconds".to_string())),
+            })
+            .transpose()?;
+        let statuses = get(STATUS_HEADER)
+            .map(|v| {
+                v.split(',')
+                    .map(|code| {
+                        // 1xx não é uma resposta final
+                        code.trim()
+                            .parse::<u16>()
+                            .ok()
+                            .filter(|code| (200..=599).contains(code))
+                            .and_then(|code| StatusCode::from_u16(code).ok())
+                            .ok_or((STATUS_HEADER, format!("Invalid status code '{}' (expected 200-599)", code.trim())))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?;
+        let fail_rate = rate(FAIL_RATE_HEADER)?;
+        let drop_rate = rate(DROP_RATE_HEADER)?;
+        
+        if latency.is_none()
+            && jitter.is_none()
+            && statuses.is_none()
+            && fail_rate.is_none()
+            && drop_rate.is_none()
+        {
+            return Ok(None);
+        }
+        
+        // Só X-RustJin-Status falha sempre; só X-RustJin-Fail-Rate falha com 500
+        Ok(Some(Self {
+            latency,
+            jitter: jitter.unwrap_or(0.0),
+            fail_rate: fail_rate.unwrap_or(if statuses.is_some() { 1.0 } else { 0.0 }),
+            statuses: statuses.unwrap_or_else(|| vec![StatusCode::INTERNAL_SERVER_ERROR]),
+            drop_rate: drop_rate.unwrap_or(0.0),
+        }))
+    }
+}
+
+fn reject(state: &AppState, body: serde_json::Value) -> Response {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint(METRICS_ENDPOINT.to_string());
+    state.metrics.increment_failed();
+    (StatusCode::BAD_REQUEST, Json(body)).into_response()
+}
+
+pub async fn layer(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let chaos = match Chaos::from_headers(request.headers()) {
+        Ok(Some(chaos)) => chaos,
+        Ok(None) => return next.run(request).await,
+        Err((header, message)) => {
+            return reject(
+                &state,
+                json!({
+                    "error": "Invalid chaos header",
+                    "header": header,
+                    "message": message
+                }),
+            )
+        }
+    };
+    
+    // Mesmo limite de /delay/:seconds
+    let max_delay_ms = (MAX_DELAY * 1000) as f64;
+    let nominal = chaos.latency.as_ref().map(Latency::nominal).unwrap_or(0.0) + chaos.jitter;
+    if nominal > max_delay_ms {
+        state.metrics.increment_delays_blocked();
+        tracing::warn!("⚠️  Delay de caos bloqueado: {} ms (max: {} ms)", nominal, max_delay_ms);
+        return reject(
+            &state,
+            json!({
+                "error": "Delay too long",
+                "max_delay": MAX_DELAY,
+                "message": format!("Maximum delay is {} seconds", MAX_DELAY)
+            }),
+        );
+    }
+    
+    // O ThreadRng não pode atravessar o await
+    let (delay_ms, drop, failure) = {
+        let mut rng = rand::thread_rng();
+        let latency = chaos.latency.as_ref().map(|l| l.sample(&mut rng)).unwrap_or(0.0);
+        let jitter = if chaos.jitter > 0.0 {
+            rng.gen_range(0.0..=chaos.jitter)
+        } else {
+            0.0
+        };
+        let delay_ms = (latency + jitter).min(max_delay_ms);
+        let drop = rng.gen_bool(chaos.drop_rate);
+        let failure = rng
+            .gen_bool(chaos.fail_rate)
+            .then(|| chaos.statuses[rng.gen_range(0..chaos.statuses.len())]);
+        (delay_ms, drop, failure)
+    };
+    
+    if delay_ms > 0.0 {
+        tokio::time::sleep(Duration::from_secs_f64(delay_ms / 1000.0)).await;
+    }
+    
+    if drop {
+        state.metrics.increment_total();
+        state.metrics.record_endpoint(METRICS_ENDPOINT.to_string());
+        state.metrics.increment_failed();
+        tracing::info!("🌪️  Caos: conexão derrubada após {:.0} ms", delay_ms);
+        
+        let mut response = StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        response.extensions_mut().insert(DropConnection);
+        return response;
+    }
+    
+    let mut applied = vec![format!("delay={:.0}ms", delay_ms)];
+    
+    let mut response = if let Some(status) = failure {
+        state.metrics.increment_total();
+        state.metrics.record_endpoint(METRICS_ENDPOINT.to_string());
+        state.metrics.increment_failed();
+        tracing::info!("🌪️  Caos: status {} após {:.0} ms", status.as_u16(), delay_ms);
+        applied.push(format!("status={}", status.as_u16()));
+        
+        (
+            status,
+            Json(json!({
+                "error": "Chaos injected",
+                "status": status.as_u16(),
+                "delay_ms": delay_ms.round()
+            })),
+        )
+            .into_response()
+    } else {
+        next.run(request).await
+    };
+    
+    if let Ok(value) = HeaderValue::from_str(&applied.join("; ")) {
+        response.headers_mut().insert(APPLIED_HEADER, value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn headers(pairs: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in pairs {
+            headers.insert(*name, value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn parse_accepts_every_distribution() {
+        assert!(matches!(Latency::parse("250"), Ok(Latency::Fixed(ms)) if ms == 250.0));
+        assert!(matches!(Latency::parse("fixed:250"), Ok(Latency::Fixed(ms)) if ms == 250.0));
+        assert!(matches!(
+            Latency::parse("uniform:100-500"),
+            Ok(Latency::Uniform(min, max)) if min == 100.0 && max == 500.0
+        ));
+        assert!(matches!(
+            Latency::parse("Normal: 300, 50"),
+            Ok(Latency::Normal { mean, std_dev }) if mean == 300.0 && std_dev == 50.0
+        ));
+        assert!(matches!(
+            Latency::parse("pareto:100,1.5"),
+            Ok(Latency::Pareto { scale, shape }) if scale == 100.0 && shape == 1.5
+        ));
+    }
+
+    #[test]
+    fn parse_rejects_invalid_specs() {
+        for spec in [
+            "",
+            "abc",
+            "fixed:1,2",
+            "uniform:500-100",
+            "normal:300",
+            "pareto:0,1.5",
+            "pareto:100,0",
+            "gamma:1,2",
+            "fixed:inf",
+            "fixed:NaN",
+        ] {
+            assert!(Latency::parse(spec).is_err(), "{} deveria ser recusado", spec);
+        }
+    }
+
+    #[test]
+    fn nominal_is_the_requested_value() {
+        assert_eq!(Latency::parse("uniform:100-500").unwrap().nominal(), 500.0);
+        assert_eq!(Latency::parse("normal:300,50").unwrap().nominal(), 300.0);
+        assert_eq!(Latency::parse("pareto:100,1.5").unwrap().nominal(), 100.0);
+    }
+
+    #[test]
+    fn samples_stay_within_each_distribution() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let fixed = Latency::parse("250").unwrap();
+        let uniform = Latency::parse("uniform:100-500").unwrap();
+        let normal = Latency::parse("normal:10,50").unwrap();
+        let pareto = Latency::parse("pareto:100,1.5").unwrap();
+        
+        for _ in 0..1000 {
+            assert_eq!(fixed.sample(&mut rng), 250.0);
+            assert!((100.0..=500.0).contains(&uniform.sample(&mut rng)));
+            assert!(normal.sample(&mut rng) >= 0.0);
+            assert!(pareto.sample(&mut rng) >= 100.0);
+        }
+        
+        // A média do uniforme fica perto do centro do intervalo
+        let mean = (0..10_000).map(|_| uniform.sample(&mut rng)).sum::<f64>() / 10_000.0;
+        assert!((mean - 300.0).abs() < 10.0, "média {}", mean);
+    }
+
+    #[test]
+    fn status_header_accepts_only_final_statuses() {
+        let chaos = Chaos::from_headers(&headers(&[(STATUS_HEADER, "503, 429")])).unwrap().unwrap();
+        assert_eq!(chaos.statuses, vec![StatusCode::SERVICE_UNAVAILABLE, StatusCode::TOO_MANY_REQUESTS]);
+        assert_eq!(chaos.fail_rate, 1.0);
+        
+        for value in ["100", "199", "600", "abc", "200,"] {
+            assert!(
+                matches!(Chaos::from_headers(&headers(&[(STATUS_HEADER, value)])), Err((STATUS_HEADER, _))),
+                "{} deveria ser recusado",
+                value
+            );
+        }
+        assert!(Chaos::from_headers(&HeaderMap::new()).unwrap().is_none());
+    }
+}
@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+
+use crate::chaos::ChaosConfig;
+use crate::fault::FaultConfig;
+use crate::graphql::GraphQLConfig;
+use crate::http3::Http3Config;
+use crate::mock::MockConfig;
+use crate::protocol::Http2Config;
+use crate::proxy::ProxyConfig;
+use crate::redact::Redaction;
+use crate::session::SessionConfig;
+use crate::tls::TlsConfig;
+use crate::url_policy::RedirectPolicy;
+
+// Configuração carregada das variáveis de ambiente na inicialização
+#[derive(Clone)]
+pub struct Config {
+    pub api_keys: ApiKeyConfig,
+    pub redaction: Redaction,
+    pub redirect: RedirectPolicy,
+    pub session: SessionConfig,
+    pub graphql: GraphQLConfig,
+    pub tls: TlsConfig,
+    pub http2: Http2Config,
+    pub http3: Http3Config,
+    pub chaos: ChaosConfig,
+    pub fault: FaultConfig,
+    pub mocks: MockConfig,
+    pub proxy: ProxyConfig,
+}
+
+impl Config {
+    pub fn from_env() -> Self {
+        let api_keys = ApiKeyConfig::from_env();
+        let redaction = Redaction::from_env(&api_keys);
+        
+        Self {
+            api_keys,
+            redaction,
+            redirect: RedirectPolicy::from_env(),
+            session: SessionConfig::from_env(),
+            graphql: GraphQLConfig::from_env(),
+            tls: TlsConfig::from_env(),
+            http2: Http2Config::from_env(),
+            http3: Http3Config::from_env(),
+            chaos: ChaosConfig::from_env(),
+            fault: FaultConfig::from_env(),
+            mocks: MockConfig::from_env(),
+            proxy: ProxyConfig::from_env(),
+        }
+    }
+}
+
+// Chaves de API aceitas por /api-key e onde procurá-las na requisição
+#[derive(Clone)]
+pub struct ApiKeyConfig {
+    pub header_name: String,
+    pub query_param: String,
+    pub cookie_name: String,
+    pub keys: HashMap<String, Vec<String>>,
+}
+
+impl ApiKeyConfig {
+    // Escopos da chave gerada quando RUSTJIN_API_KEYS não está definida
+    const GENERATED_SCOPES: &'static str = "read,write,admin";
+
+    fn from_env() -> Self {
+        // Sem chaves configuradas, uma chave aleatória vale enquanto o processo estiver de pé
+        let keys = std::env::var("RUSTJIN_API_KEYS")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .unwrap_or_else(|| {
+                let key = format!("rustjin-{}", uuid::Uuid::new_v4().simple());
+                tracing::warn!(
+                    "🔑 RUSTJIN_API_KEYS não definida; chave gerada ({}): {}",
+                    Self::GENERATED_SCOPES,
+                    key
+                );
+                format!("{}={}", key, Self::GENERATED_SCOPES)
+            });
+        
+        Self {
+            header_name: env_or("RUSTJIN_API_KEY_HEADER", "X-API-Key"),
+            query_param: env_or("RUSTJIN_API_KEY_QUERY", "api_key"),
+            cookie_name: env_or("RUSTJIN_API_KEY_COOKIE", "api_key"),
+            keys: parse_api_keys(&keys),
+        }
+    }
+}
+
+// Formato: "chave1=escopo1,escopo2;chave2=escopo3"
+fn parse_api_keys(raw: &str) -> HashMap<String, Vec<String>> {
+    raw.split(';')
+        .filter_map(|entry| {
+            let (key, scopes) = entry.split_once('=').unwrap_or((entry, ""));
+            let key = key.trim();
+            if key.is_empty() {
+                return None;
+            }
+            let scopes = scopes
+                .split(',')
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+                .collect();
+            Some((key.to_string(), scopes))
+        })
+        .collect()
+}
+
+pub fn env_or(name: &str, default: &str) -> String {
+    std::env::var(name)
+        .ok()
+        .filter(|v| !v.trim().is_empty())
+        .unwrap_or_else(|| default.to_string())
+}
+
+// Lista separada por vírgulas, normalizada para minúsculas
+pub fn env_list(name: &str, default: &str) -> Vec<String> {
+    env_or(name, default)
+        .split(',')
+        .map(|item| item.trim().to_lowercase())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+pub fn env_flag(name: &str) -> bool {
+    matches!(
+        std::env::var(name).map(|v| v.trim().to_lowercase()).as_deref(),
+        Ok("1" | "true" | "yes" | "on")
+    )
+}
@@ -0,0 +1,300 @@
+use axum::http::HeaderMap;
+use cookie::{
+    time::{Duration, OffsetDateTime},
+    Cookie, SameSite,
+};
+use std::collections::HashMap;
+
+// Parâmetros de /cookies/set e /cookies/delete que configuram atributos, não cookies
+pub const ATTRIBUTE_PARAMS: [&str; 9] = [
+    "path",
+    "domain",
+    "expires",
+    "max_age",
+    "secure",
+    "httponly",
+    "samesite",
+    "partitioned",
+    "redirect",
+];
+
+pub fn is_attribute_param(name: &str) -> bool {
+    ATTRIBUTE_PARAMS.contains(&name.to_lowercase().as_str())
+}
+
+// Lê todos os headers Cookie (no HTTP/2 podem vir separados); o primeiro valor de cada nome vence
+pub fn parse_cookie_header(headers: &HeaderMap) -> HashMap<String, String> {
+    let mut cookies = HashMap::new();
+    
+    for header in headers.get_all("cookie") {
+        let Ok(header) = header.to_str() else {
+            continue;
+        };
+        
+        for cookie in Cookie::split_parse(header).flatten() {
+            cookies
+                .entry(cookie.name().to_string())
+                .or_insert_with(|| cookie.value().to_string());
+        }
+    }
+    
+    cookies
+}
+
+// Atributos do Set-Cookie controlados pelo cliente via query string
+#[derive(Default)]
+pub struct CookieAttributes {
+    path: Option<String>,
+    domain: Option<String>,
+    expires: Option<OffsetDateTime>,
+    max_age: Option<i64>,
+    secure: bool,
+    http_only: bool,
+    same_site: Option<SameSite>,
+    partitioned: bool,
+    pub redirect: bool,
+}
+
+impl CookieAttributes {
+    pub fn from_params(params: &[(String, String)]) -> Result<Self, String> {
+        let mut attributes = Self::default();
+        
+        for (name, value) in params {
+            match name.to_lowercase().as_str() {
+                "path" => {
+                    if !value.starts_with('/') || !is_attribute_value(value) {
+                        return Err(format!("Invalid Path attribute: '{}'", value));
+                    }
+                    attributes.path = Some(value.clone());
+                }
+                "domain" => {
+                    if value.is_empty() || !is_attribute_value(value) {
+                        return Err(format!("Invalid Domain attribute: '{}'", value));
+                    }
+                    attributes.domain = Some(value.clone());
+                }
+                "expires" => attributes.expires = Some(parse_expires(value)?),
+                "max_age" => {
+                    let seconds = value
+                        .parse()
+                        .map_err(|_| format!("Invalid Max-Age attribute: '{}'", value))?;
+                    attributes.max_age = Some(seconds);
+                }
+                "secure" => attributes.secure = parse_flag(name, value)?,
+                "httponly" => attributes.http_only = parse_flag(name, value)?,
+                "partitioned" => attributes.partitioned = parse_flag(name, value)?,
+                "redirect" => attributes.redirect = parse_flag(name, value)?,
+                "samesite" => {
+                    attributes.same_site = Some(match value.to_lowercase().as_str() {
+                        "strict" => SameSite::Strict,
+                        "lax" => SameSite::Lax,
+                        "none" => SameSite::None,
+                        _ => {
+                            return Err(format!(
+                                "Invalid SameSite attribute: '{}' (expected Strict, Lax or None)",
+                                value
+                            ))
+                        }
+                    });
+                }
+                _ => {}
+            }
+        }
+        
+        Ok(attributes)
+    }
+
+    fn apply(&self, mut cookie: Cookie<'static>) -> Cookie<'static> {
+        if let Some(path) = &self.path {
+            cookie.set_path(path.clone());
+        }
+        if let Some(domain) = &self.domain {
+            cookie.set_domain(domain.clone());
+        }
+        if let Some(expires) = self.expires {
+            cookie.set_expires(expires);
+        }
+        if let Some(max_age) = self.max_age {
+            cookie.set_max_age(Duration::seconds(max_age));
+        }
+        if self.secure {
+            cookie.set_secure(true);
+        }
+        if self.http_only {
+            cookie.set_http_only(true);
+        }
+        if let Some(same_site) = self.same_site {
+            cookie.set_same_site(same_site);
+        }
+        // Cookies particionados (CHIPS) sempre saem com Secure
+        if self.partitioned {
+            cookie.set_partitioned(true);
+        }
+        cookie
+    }
+
+    pub fn set_cookie(&self, name: &str, value: &str) -> Result<Cookie<'static>, String> {
+        validate_name(name)?;
+        validate_value(name, value)?;
+        
+        Ok(self.apply(Cookie::new(name.to_string(), value.to_string())))
+    }
+
+    // Para remover, Path e Domain precisam ser os mesmos usados ao definir o cookie
+    pub fn removal_cookie(&self, name: &str) -> Result<Cookie<'static>, String> {
+        validate_name(name)?;
+        
+        let mut cookie = self.apply(Cookie::new(name.to_string(), ""));
+        cookie.make_removal();
+        Ok(cookie)
+    }
+}
+
+// cookie-name = token (RFC 6265, seção 4.1.1)
+fn validate_name(name: &str) -> Result<(), String> {
+    let is_token = !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b));
+    
+    if is_token {
+        Ok(())
+    } else {
+        Err(format!("Invalid cookie name: '{}'", name))
+    }
+}
+
+// cookie-value = *cookie-octet / ( DQUOTE *cookie-octet DQUOTE )
+fn validate_value(name: &str, value: &str) -> Result<(), String> {
+    let inner = value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value);
+    
+    let is_cookie_octet =
+        |b: u8| matches!(b, 0x21 | 0x23..=0x2B | 0x2D..=0x3A | 0x3C..=0x5B | 0x5D..=0x7E);
+    
+    if inner.bytes().all(is_cookie_octet) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid value for cookie '{}': whitespace, DQUOTE, comma, semicolon and backslash are not allowed",
+            name
+        ))
+    }
+}
+
+fn is_attribute_value(value: &str) -> bool {
+    value.bytes().all(|b| (0x20..0x7F).contains(&b) && b != b';')
+}
+
+fn parse_flag(name: &str, value: &str) -> Result<bool, String> {
+    match value.to_lowercase().as_str() {
+        "" | "1" | "true" | "yes" | "on" => Ok(true),
+        "0" | "false" | "no" | "off" => Ok(false),
+        _ => Err(format!("Invalid value for '{}': '{}' (expected true or false)", name, value)),
+    }
+}
+
+// Aceita timestamp Unix, HTTP-date (RFC 7231) ou RFC 3339
+fn parse_expires(value: &str) -> Result<OffsetDateTime, String> {
+    let timestamp = value.parse::<i64>().ok().or_else(|| {
+        chrono::DateTime::parse_from_rfc2822(value)
+            .or_else(|_| chrono::DateTime::parse_from_rfc3339(value))
+            .ok()
+            .map(|date| date.timestamp())
+    });
+    
+    timestamp
+        .and_then(|ts| OffsetDateTime::from_unix_timestamp(ts).ok())
+        .ok_or_else(|| format!("Invalid Expires attribute: '{}'", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn names_must_be_tokens() {
+        for name in ["session", "a.b-c_d", "x!#$%&'*+^`|~"] {
+            assert!(validate_name(name).is_ok(), "{}", name);
+        }
+        for name in ["", "a b", "a=b", "a;b", "a,b", "(a)", "\"a\"", "ç", "a\tb"] {
+            assert!(validate_name(name).is_err(), "{:?}", name);
+        }
+    }
+
+    #[test]
+    fn values_must_be_cookie_octets() {
+        for value in ["", "abc", "a=b", "a/b:c", "\"quoted\"", "\"\""] {
+            assert!(validate_value("n", value).is_ok(), "{:?}", value);
+        }
+        for value in ["a b", "a;b", "a,b", "a\\b", "a\"b", "\"", "\"open", "ç", "a\nb"] {
+            assert!(validate_value("n", value).is_err(), "{:?}", value);
+        }
+    }
+
+    #[test]
+    fn set_cookie_applies_attributes() {
+        let attributes = CookieAttributes::from_params(&params(&[
+            ("path", "/app"),
+            ("max_age", "60"),
+            ("secure", ""),
+            ("httponly", "true"),
+            ("samesite", "strict"),
+        ]))
+        .unwrap();
+        let cookie = attributes.set_cookie("theme", "dark").unwrap().to_string();
+        
+        for part in ["theme=dark", "Path=/app", "Max-Age=60", "Secure", "HttpOnly", "SameSite=Strict"] {
+            assert!(cookie.contains(part), "{} in {}", part, cookie);
+        }
+    }
+
+    #[test]
+    fn invalid_attributes_are_rejected() {
+        for pair in [
+            ("path", "app"),
+            ("path", "/a;b"),
+            ("domain", ""),
+            ("max_age", "soon"),
+            ("secure", "maybe"),
+            ("samesite", "sometimes"),
+            ("expires", "tomorrow"),
+        ] {
+            assert!(CookieAttributes::from_params(&params(&[pair])).is_err(), "{:?}", pair);
+        }
+    }
+
+    #[test]
+    fn expires_accepts_timestamps_and_dates() {
+        for value in ["0", "Wed, 21 Oct 2015 07:28:00 GMT", "2015-10-21T07:28:00Z"] {
+            assert!(parse_expires(value).is_ok(), "{}", value);
+        }
+    }
+
+    #[test]
+    fn removal_cookie_expires_immediately() {
+        let cookie = CookieAttributes::default().removal_cookie("theme").unwrap().to_string();
+        
+        assert!(cookie.starts_with("theme="), "{}", cookie);
+        assert!(cookie.contains("Max-Age=0"), "{}", cookie);
+        assert!(CookieAttributes::default().removal_cookie("bad name").is_err());
+    }
+
+    #[test]
+    fn cookie_headers_are_merged_and_first_value_wins() {
+        let mut headers = HeaderMap::new();
+        headers.append("cookie", "a=1; b=2".parse().unwrap());
+        headers.append("cookie", "a=3; c=4".parse().unwrap());
+        
+        let cookies = parse_cookie_header(&headers);
+        assert_eq!(cookies.len(), 3);
+        assert_eq!(cookies["a"], "1");
+        assert_eq!(cookies["c"], "4");
+    }
+}
@@ -0,0 +1,276 @@
+use axum::{
+    extract::Path,
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+    routing::get,
+    Router,
+};
+use serde_json::json;
+use std::{sync::Arc, time::Duration};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::TcpStream,
+};
+
+use crate::config::env_or;
+use crate::{AppState, MAX_DELAY};
+
+// Corpo anunciado pelas falhas que cortam a resposta no meio
+const DECLARED_BODY_LEN: usize = 1024;
+
+// Tempo para os headers chegarem ao cliente antes do RST
+const RESET_GRACE: Duration = Duration::from_millis(100);
+
+// Fim dos headers da requisição; além disso a conexão é tratada como está
+const MAX_REQUEST_HEAD: usize = 64 * 1024;
+
+// Menor limite aceito para /fault/hang: bem acima do MAX_DELAY dos ecos, para não virar um delay
+const MIN_HANG_SECONDS: u64 = MAX_DELAY * 30;
+
+// Sem RUSTJIN_FAULT_HANG_SECONDS, /fault/hang segura a conexão até o cliente desistir
+#[derive(Clone)]
+pub struct FaultConfig {
+    pub hang_limit: Option<Duration>,
+}
+
+impl FaultConfig {
+    pub fn from_env() -> Self {
+        Self {
+            hang_limit: env_or("RUSTJIN_FAULT_HANG_SECONDS", "")
+                .trim()
+                .parse::<u64>()
+                .ok()
+                .map(|seconds| Duration::from_secs(seconds.max(MIN_HANG_SECONDS))),
+        }
+    }
+}
+
+// Conexão crua (TCP ou TLS sobre TCP) em que as falhas são escritas diretamente
+pub trait RawStream: AsyncRead + AsyncWrite + Unpin + Send + 'static {
+    fn tcp(&self) -> &TcpStream;
+}
+
+impl RawStream for TcpStream {
+    fn tcp(&self) -> &TcpStream {
+        self
+    }
+}
+
+impl RawStream for tokio_rustls::server::TlsStream<TcpStream> {
+    fn tcp(&self) -> &TcpStream {
+        self.get_ref().0
+    }
+}
+
+// Falhas no nível do protocolo, fora do alcance de um handler comum
+#[derive(Clone, Copy, PartialEq)]
+pub enum Fault {
+    Reset,
+    ResetAfterHeaders,
+    CloseMidBody,
+    ContentLengthShort,
+    ContentLengthLong,
+    BadChunked,
+    GarbageStatus,
+    Hang,
+}
+
+impl Fault {
+    const ALL: [(&'static str, Fault, &'static str); 8] = [
+        ("reset", Fault::Reset, "RST before any response byte"),
+        ("reset-after-headers", Fault::ResetAfterHeaders, "RST after the status line and headers"),
+        ("close-mid-body", Fault::CloseMidBody, "Chunked body closed (FIN) without the last chunk"),
+        ("content-length-short", Fault::ContentLengthShort, "Content-Length larger than the body, then FIN"),
+        ("content-length-long", Fault::ContentLengthLong, "Body longer than Content-Length"),
+        ("bad-chunked", Fault::BadChunked, "Invalid chunk size in a chunked body"),
+        ("garbage-status", Fault::GarbageStatus, "Malformed status line"),
+        ("hang", Fault::Hang, "Reads the request and never responds"),
+    ];
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|(n, _, _)| *n == name)
+            .map(|(_, fault, _)| *fault)
+    }
+
+    fn name(self) -> &'static str {
+        Self::ALL
+            .iter()
+            .find(|(_, fault, _)| *fault == self)
+            .map(|(name, _, _)| *name)
+            .unwrap_or("unknown")
+    }
+
+    // "GET /fault/reset HTTP/1.1": só HTTP/1.x, onde cada byte da resposta é nosso
+    pub fn from_request_line(head: &[u8]) -> Option<Self> {
+        let line = head.split(|b| *b == b'\n').next()?;
+        let line = std::str::from_utf8(line).ok()?.trim_end();
+        let mut parts = line.split(' ');
+        let (_method, target, version) = (parts.next()?, parts.next()?, parts.next()?);
+        
+        if !version.starts_with("HTTP/1.") {
+            return None;
+        }
+        
+        let path = target.split('?').next()?;
+        Self::from_name(path.strip_prefix("/fault/")?)
+    }
+}
+
+// Lê o restante dos headers para que o fechamento da conexão não vire RST por dados não lidos
+async fn read_request_head<S: RawStream>(stream: &mut S, head: &mut Vec<u8>) {
+    let mut chunk = [0u8; 4096];
+    
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") && head.len() < MAX_REQUEST_HEAD {
+        match stream.read(&mut chunk).await {
+            Ok(0) | Err(_) => return,
+            Ok(n) => head.extend_from_slice(&chunk[..n]),
+        }
+    }
+}
+
+fn reset<S: RawStream>(stream: S) {
+    if let Err(e) = stream.tcp().set_zero_linger() {
+        tracing::warn!("⚠️  Não foi possível ativar SO_LINGER=0: {}", e);
+    }
+    drop(stream);
+}
+
+pub async fn inject<S: RawStream>(mut stream: S, mut head: Vec<u8>, fault: Fault, state: &AppState) {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint(format!("/fault/{}", fault.name()));
+    state.metrics.increment_success();
+    tracing::info!("💥 Falha injetada: {}", fault.name());
+    
+    read_request_head(&mut stream, &mut head).await;
+    
+    let body = vec![b'x'; DECLARED_BODY_LEN];
+    let half = &body[..DECLARED_BODY_LEN / 2];
+    let headers = |framing: &str| {
+        format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nConnection: close\r\n{}\r\n\r\n",
+            framing
+        )
+        .into_bytes()
+    };
+    
+    let response: Vec<u8> = match fault {
+        Fault::Reset => {
+            reset(stream);
+            return;
+        }
+        Fault::ResetAfterHeaders => headers(&format!("Content-Length: {}", DECLARED_BODY_LEN)),
+        Fault::CloseMidBody => {
+            let mut response = headers("Transfer-Encoding: chunked");
+            response.extend_from_slice(format!("{:x}\r\n", half.len()).as_bytes());
+            response.extend_from_slice(half);
+            response.extend_from_slice(b"\r\n");
+            response
+        }
+        Fault::ContentLengthShort => {
+            let mut response = headers(&format!("Content-Length: {}", DECLARED_BODY_LEN));
+            response.extend_from_slice(half);
+            response
+        }
+        Fault::ContentLengthLong => {
+            let mut response = headers(&format!("Content-Length: {}", half.len()));
+            response.extend_from_slice(&body);
+            response
+        }
+        Fault::BadChunked => {
+            let mut response = headers("Transfer-Encoding: chunked");
+            response.extend_from_slice(b"zz\r\nnot a chunk size\r\n0\r\n\r\n");
+            response
+        }
+        Fault::GarbageStatus => b"HTTP/1.1 ABC Garbage\x00Status\r\nContent-Length: 0\r\n\r\n".to_vec(),
+        Fault::Hang => {
+            // Descarta o que vier até o cliente desistir (ou o limite configurado passar)
+            let mut chunk = [0u8; 4096];
+            let drain = async {
+                while let Ok(n) = stream.read(&mut chunk).await {
+                    if n == 0 {
+                        break;
+                    }
+                }
+            };
+            match state.config.fault.hang_limit {
+                Some(limit) => {
+                    let _ = tokio::time::timeout(limit, drain).await;
+                }
+                None => drain.await,
+            }
+            return;
+        }
+    };
+    
+    if stream.write_all(&response).await.is_err() || stream.flush().await.is_err() {
+        return;
+    }
+    
+    if matches!(fault, Fault::ResetAfterHeaders) {
+        tokio::time::sleep(RESET_GRACE).await;
+        reset(stream);
+    } else {
+        let _ = stream.shutdown().await;
+    }
+}
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/fault", get(handle_fault_list))
+        .route("/fault/:name", get(handle_fault_fallback))
+}
+
+async fn handle_fault_list(
+    axum::extract::State(state): axum::extract::State<Arc<AppState>>,
+) -> impl IntoResponse {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/fault".to_string());
+    state.metrics.increment_success();
+    
+    let faults: Vec<_> = Fault::ALL
+        .iter()
+        .map(|(name, _, description)| {
+            json!({
+                "path": format!("/fault/{}", name),
+                "description": description
+            })
+        })
+        .collect();
+    
+    Json(json!({
+        "faults": faults,
+        "note": "Faults apply to the first HTTP/1.x request of a connection"
+    }))
+}
+
+// Chega aqui o que o accept loop não interceptou: HTTP/2, HTTP/3 ou conexão reaproveitada
+async fn handle_fault_fallback(
+    axum::extract::State(state): axum::extract::State<Arc<AppState>>,
+    Path(name): Path<String>,
+) -> Response {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/fault/:name".to_string());
+    state.metrics.increment_failed();
+    
+    if Fault::from_name(&name).is_none() {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(json!({
+                "error": "Unknown fault",
+                "available": Fault::ALL.iter().map(|(name, _, _)| *name).collect::<Vec<_>>()
+            })),
+        )
+            .into_response();
+    }
+    
+    (
+        StatusCode::BAD_REQUEST,
+        Json(json!({
+            "error": "Fault not injected",
+            "message": "Faults apply only to the first HTTP/1.x request of a connection; open a new HTTP/1.1 connection"
+        })),
+    )
+        .into_response()
+}
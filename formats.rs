@@ -0,0 +1,393 @@
+use quick_xml::{events::Event, Reader};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::negotiate;
+
+// Aninhamento máximo aceito no XML recebido, o mesmo limite padrão do serde_json
+const MAX_XML_DEPTH: usize = 128;
+
+// Formatos de serialização oferecidos pelos endpoints de eco
+#[derive(Clone, Copy, PartialEq)]
+pub enum Format {
+    Json,
+    Xml,
+    Yaml,
+    MessagePack,
+    Cbor,
+    Toml,
+}
+
+pub const NAMES: [&str; 6] = ["json", "xml", "yaml", "msgpack", "cbor", "toml"];
+
+// Ordem de preferência usada em empates de q. Navegadores pedem text/html antes de
+// application/xml, então para eles a resposta continua sendo JSON.
+const MEDIA_TYPES: [(&str, Format); 7] = [
+    ("application/json", Format::Json),
+    ("text/html", Format::Json),
+    ("application/xml", Format::Xml),
+    ("application/yaml", Format::Yaml),
+    ("application/msgpack", Format::MessagePack),
+    ("application/cbor", Format::Cbor),
+    ("application/toml", Format::Toml),
+];
+
+impl Format {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "xml" => Some(Self::Xml),
+            "yaml" | "yml" => Some(Self::Yaml),
+            "msgpack" | "messagepack" => Some(Self::MessagePack),
+            "cbor" => Some(Self::Cbor),
+            "toml" => Some(Self::Toml),
+            _ => None,
+        }
+    }
+
+    // Reconhece também os aliases mais comuns de cada media type
+    pub fn from_media_type(media_type: &str) -> Option<Self> {
+        let media_type = media_type.split(';').next()?.trim().to_lowercase();
+        
+        match media_type.as_str() {
+            "application/json" => Some(Self::Json),
+            "application/xml" | "text/xml" => Some(Self::Xml),
+            "application/yaml" | "application/x-yaml" | "text/yaml" | "text/x-yaml" => Some(Self::Yaml),
+            "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack" => {
+                Some(Self::MessagePack)
+            }
+            "application/cbor" => Some(Self::Cbor),
+            "application/toml" => Some(Self::Toml),
+            t if t.ends_with("+json") => Some(Self::Json),
+            t if t.ends_with("+xml") => Some(Self::Xml),
+            t if t.ends_with("+cbor") => Some(Self::Cbor),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Xml => "xml",
+            Self::Yaml => "yaml",
+            Self::MessagePack => "msgpack",
+            Self::Cbor => "cbor",
+            Self::Toml => "toml",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::Xml => "application/xml",
+            Self::Yaml => "application/yaml",
+            Self::MessagePack => "application/msgpack",
+            Self::Cbor => "application/cbor",
+            Self::Toml => "application/toml",
+        }
+    }
+
+    // ?format= tem precedência sobre o Accept; sem nenhum dos dois a resposta é JSON
+    pub fn select(param: Option<&str>, accept: Option<&str>) -> Result<Self, String> {
+        if let Some(name) = param {
+            return Self::from_name(name)
+                .ok_or_else(|| format!("Unsupported format: '{}'", name));
+        }
+        
+        let available = MEDIA_TYPES.map(|(media_type, _)| media_type);
+        Ok(negotiate::best_media_type(accept, &available)
+            .and_then(|media_type| MEDIA_TYPES.iter().find(|(t, _)| *t == media_type))
+            .map(|(_, format)| *format)
+            .unwrap_or(Self::Json))
+    }
+}
+
+pub fn encode<T: Serialize>(format: Format, value: &T) -> Result<Vec<u8>, String> {
+    match format {
+        Format::Json => serde_json::to_vec(value).map_err(|e| e.to_string()),
+        Format::Yaml => serde_yaml::to_string(value)
+            .map(String::into_bytes)
+            .map_err(|e| e.to_string()),
+        Format::MessagePack => rmp_serde::to_vec_named(value).map_err(|e| e.to_string()),
+        Format::Cbor => {
+            let mut bytes = Vec::new();
+            ciborium::into_writer(value, &mut bytes).map_err(|e| e.to_string())?;
+            Ok(bytes)
+        }
+        Format::Toml => {
+            // TOML não tem null: campos nulos são omitidos
+            let mut value = serde_json::to_value(value).map_err(|e| e.to_string())?;
+            strip_nulls(&mut value);
+            toml::to_string_pretty(&value)
+                .map(String::into_bytes)
+                .map_err(|e| e.to_string())
+        }
+        Format::Xml => {
+            let value = serde_json::to_value(value).map_err(|e| e.to_string())?;
+            let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+            write_xml(&mut xml, "request", &value, 0);
+            Ok(xml.into_bytes())
+        }
+    }
+}
+
+pub fn decode(format: Format, body: &[u8]) -> Result<Value, String> {
+    match format {
+        Format::Json => serde_json::from_slice(body).map_err(|e| e.to_string()),
+        Format::Yaml => serde_yaml::from_slice(body).map_err(|e| e.to_string()),
+        Format::MessagePack => rmp_serde::from_slice(body).map_err(|e| e.to_string()),
+        Format::Cbor => ciborium::from_reader(body).map_err(|e| e.to_string()),
+        Format::Toml => {
+            let text = std::str::from_utf8(body).map_err(|e| e.to_string())?;
+            toml::from_str(text).map_err(|e| e.to_string())
+        }
+        Format::Xml => {
+            let text = std::str::from_utf8(body).map_err(|e| e.to_string())?;
+            parse_xml(text)
+        }
+    }
+}
+
+fn strip_nulls(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|_, v| !v.is_null());
+            map.values_mut().for_each(strip_nulls);
+        }
+        Value::Array(items) => {
+            items.retain(|v| !v.is_null());
+            items.iter_mut().for_each(strip_nulls);
+        }
+        _ => {}
+    }
+}
+
+// Char da produção 2.2 do XML 1.0; controles como NUL e U+FFFE não podem aparecer nem escapados
+fn is_xml_char(c: char) -> bool {
+    matches!(
+        c,
+        '\t' | '\n' | '\r' | '\u{20}'..='\u{D7FF}' | '\u{E000}'..='\u{FFFD}' | '\u{10000}'..
+    )
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars().filter(|c| is_xml_char(*c)) {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Chaves como "content-type" servem de nome de elemento; as demais viram <entry key="...">
+fn is_xml_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        && !name.to_lowercase().starts_with("xml")
+}
+
+fn write_xml(out: &mut String, name: &str, value: &Value, depth: usize) {
+    let indent = "  ".repeat(depth);
+    let (open, close) = if is_xml_name(name) {
+        (name.to_string(), name.to_string())
+    } else {
+        (format!("entry key=\"{}\"", escape_xml(name)), "entry".to_string())
+    };
+    
+    match value {
+        Value::Null => out.push_str(&format!("{}<{}/>\n", indent, open)),
+        Value::Object(map) => {
+            out.push_str(&format!("{}<{}>\n", indent, open));
+            for (key, value) in map {
+                write_xml(out, key, value, depth + 1);
+            }
+            out.push_str(&format!("{}</{}>\n", indent, close));
+        }
+        Value::Array(items) => {
+            out.push_str(&format!("{}<{}>\n", indent, open));
+            for item in items {
+                write_xml(out, "item", item, depth + 1);
+            }
+            out.push_str(&format!("{}</{}>\n", indent, close));
+        }
+        Value::String(text) => {
+            out.push_str(&format!("{}<{}>{}</{}>\n", indent, open, escape_xml(text), close))
+        }
+        other => out.push_str(&format!("{}<{}>{}</{}>\n", indent, open, other, close)),
+    }
+}
+
+// Converte XML em JSON: atributos viram "@nome", elementos repetidos viram arrays e
+// texto misturado com filhos vai para "#text"
+fn parse_xml(text: &str) -> Result<Value, String> {
+    let mut reader = Reader::from_str(text);
+    reader.config_mut().trim_text(true);
+    
+    // Elementos abertos: nome, filhos/atributos e texto acumulado
+    let mut stack: Vec<(String, Map<String, Value>, String)> = Vec::new();
+    
+    loop {
+        let event = reader.read_event().map_err(|e| e.to_string())?;
+        
+        match event {
+            Event::Start(ref element) | Event::Empty(ref element) => {
+                if stack.len() >= MAX_XML_DEPTH {
+                    return Err(format!("XML nesting exceeds {} levels", MAX_XML_DEPTH));
+                }
+                
+                let name = String::from_utf8_lossy(element.name().as_ref()).to_string();
+                let mut attributes = Map::new();
+                
+                for attribute in element.attributes() {
+                    let attribute = attribute.map_err(|e| e.to_string())?;
+                    let key = String::from_utf8_lossy(attribute.key.as_ref()).to_string();
+                    let value = attribute.unescape_value().map_err(|e| e.to_string())?;
+                    attributes.insert(format!("@{}", key), Value::String(value.to_string()));
+                }
+                
+                stack.push((name, attributes, String::new()));
+                if !matches!(event, Event::Empty(_)) {
+                    continue;
+                }
+            }
+            Event::Text(text) => {
+                if let Some((_, _, buffer)) = stack.last_mut() {
+                    buffer.push_str(&text.unescape().map_err(|e| e.to_string())?);
+                }
+                continue;
+            }
+            Event::CData(data) => {
+                if let Some((_, _, buffer)) = stack.last_mut() {
+                    buffer.push_str(&String::from_utf8_lossy(&data));
+                }
+                continue;
+            }
+            Event::End(_) => {}
+            Event::Eof => return Err("Unexpected end of XML document".to_string()),
+            _ => continue,
+        }
+        
+        // Fechamento de elemento (End ou Empty)
+        let Some((name, mut children, text)) = stack.pop() else {
+            return Err("Unbalanced XML document".to_string());
+        };
+        
+        let value = if children.is_empty() {
+            Value::String(text)
+        } else {
+            if !text.is_empty() {
+                children.insert("#text".to_string(), Value::String(text));
+            }
+            Value::Object(children)
+        };
+        
+        match stack.last_mut() {
+            Some((_, parent, _)) => match parent.get_mut(&name) {
+                Some(Value::Array(items)) => items.push(value),
+                Some(existing) => {
+                    let first = existing.take();
+                    *existing = Value::Array(vec![first, value]);
+                }
+                None => {
+                    parent.insert(name, value);
+                }
+            },
+            None => {
+                let mut root = Map::new();
+                root.insert(name, value);
+                return Ok(Value::Object(root));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn nested(depth: usize) -> String {
+        format!("{}x{}", "<a>".repeat(depth), "</a>".repeat(depth))
+    }
+
+    #[test]
+    fn xml_within_depth_limit_is_parsed() {
+        let value = parse_xml(&nested(MAX_XML_DEPTH)).unwrap();
+        
+        let mut level = &value;
+        for _ in 0..MAX_XML_DEPTH {
+            level = &level["a"];
+        }
+        assert_eq!(level, "x");
+    }
+
+    #[test]
+    fn xml_deeper_than_limit_is_rejected() {
+        let error = parse_xml(&nested(MAX_XML_DEPTH + 1)).unwrap_err();
+        assert!(error.contains("nesting"), "{}", error);
+        
+        // Sem fechamento, o limite vale do mesmo jeito
+        assert!(parse_xml(&"<a>".repeat(50_000)).is_err());
+        let empty_too_deep = format!(
+            "{}<b/>{}",
+            "<a>".repeat(MAX_XML_DEPTH),
+            "</a>".repeat(MAX_XML_DEPTH)
+        );
+        assert!(parse_xml(&empty_too_deep).is_err());
+    }
+
+    #[test]
+    fn xml_attributes_repeats_and_mixed_text() {
+        let value = parse_xml(r#"<r id="1"><i>a</i><i>b</i>tail<e/></r>"#).unwrap();
+        
+        assert_eq!(
+            value,
+            json!({ "r": { "@id": "1", "i": ["a", "b"], "e": "", "#text": "tail" } })
+        );
+    }
+
+    #[test]
+    fn malformed_xml_is_rejected() {
+        assert!(parse_xml("<a><b></a>").is_err());
+        assert!(parse_xml("<a>").is_err());
+        assert!(parse_xml("").is_err());
+    }
+
+    #[test]
+    fn escape_xml_drops_invalid_characters() {
+        assert_eq!(escape_xml("a\u{0}b\u{1b}c\u{FFFE}d\u{FFFF}"), "abcd");
+        assert_eq!(escape_xml("tab\there\r\n"), "tab\there\r\n");
+        assert_eq!(escape_xml("<&\">"), "&lt;&amp;&quot;&gt;");
+        assert_eq!(escape_xml("ção 🦀"), "ção 🦀");
+    }
+
+    #[test]
+    fn encoded_xml_round_trips_without_control_characters() {
+        let value = json!({ "data": "bell\u{7}", "odd key": "v", "list": [1, 2] });
+        let xml = String::from_utf8(encode(Format::Xml, &value).unwrap()).unwrap();
+        
+        assert!(!xml.contains('\u{7}'));
+        let parsed = decode(Format::Xml, xml.as_bytes()).unwrap();
+        assert_eq!(parsed["request"]["data"], "bell");
+        assert_eq!(parsed["request"]["entry"]["@key"], "odd key");
+        assert_eq!(parsed["request"]["list"]["item"], json!(["1", "2"]));
+    }
+
+    #[test]
+    fn serde_formats_round_trip() {
+        let value = json!({ "name": "ana", "tags": ["a", "b"], "n": 3 });
+        
+        for format in [Format::Json, Format::Yaml, Format::MessagePack, Format::Cbor, Format::Toml] {
+            let bytes = encode(format, &value).unwrap();
+            assert_eq!(decode(format, &bytes).unwrap(), value, "{}", format.name());
+        }
+    }
+}
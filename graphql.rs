@@ -0,0 +1,525 @@
+use async_graphql::{
+    extensions::apollo_persisted_queries::{ApolloPersistedQueries, LruCacheStorage},
+    http::{GraphiQLSource, WebSocket, WebSocketProtocols, WsMessage, ALL_WEBSOCKET_PROTOCOLS},
+    BatchRequest, Context, Data, Enum, Error, Object, Result, Schema, SimpleObject, Subscription,
+    ID,
+};
+use axum::{
+    body::Bytes,
+    extract::{
+        ws::{CloseFrame, Message, WebSocketUpgrade},
+        Query,
+    },
+    http::{HeaderMap, StatusCode, Uri},
+    response::{Html, IntoResponse, Response},
+    routing::get,
+    Extension, Json, Router,
+};
+use futures_util::{future, SinkExt, Stream, StreamExt};
+use serde_json::json;
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+    time::Duration,
+};
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::{BroadcastStream, IntervalStream};
+
+use crate::config::{env_flag, env_or};
+use crate::{AppState, RequestInfo, MAX_DELAY};
+
+// Mesmo limite de /stream/:n (o de /delay/:seconds vem de crate::MAX_DELAY)
+const MAX_TICKS: u32 = 100;
+
+// Limites do store em memória compartilhado por todos os clientes
+const MAX_ITEMS: usize = 1000;
+const MAX_FIELD_LENGTH: usize = 4096;
+
+// Consultas persistidas (Apollo APQ) mantidas em cache
+const PERSISTED_QUERY_CACHE: usize = 512;
+
+// Operações aceitas em um único POST em batch
+const MAX_BATCH: usize = 16;
+
+pub type RustJinSchema = Schema<QueryRoot, MutationRoot, SubscriptionRoot>;
+
+// Limites de execução de consultas. Os padrões comportam a consulta de introspection do GraphiQL.
+#[derive(Clone)]
+pub struct GraphQLConfig {
+    max_depth: usize,
+    max_complexity: usize,
+    introspection: bool,
+}
+
+impl GraphQLConfig {
+    pub fn from_env() -> Self {
+        Self {
+            max_depth: env_or("RUSTJIN_GRAPHQL_MAX_DEPTH", "16").parse().unwrap_or(16),
+            max_complexity: env_or("RUSTJIN_GRAPHQL_MAX_COMPLEXITY", "1000")
+                .parse()
+                .unwrap_or(1000),
+            introspection: !env_flag("RUSTJIN_GRAPHQL_DISABLE_INTROSPECTION"),
+        }
+    }
+
+    fn schema(&self) -> RustJinSchema {
+        let (events, _) = broadcast::channel(64);
+        
+        let builder = Schema::build(QueryRoot, MutationRoot, SubscriptionRoot)
+            .data(Store {
+                items: Mutex::new(BTreeMap::new()),
+                next_id: AtomicU64::new(1),
+                events,
+            })
+            .extension(ApolloPersistedQueries::new(LruCacheStorage::new(
+                PERSISTED_QUERY_CACHE,
+            )))
+            .limit_depth(self.max_depth)
+            .limit_complexity(self.max_complexity);
+        
+        if self.introspection {
+            builder.finish()
+        } else {
+            builder.disable_introspection().finish()
+        }
+    }
+}
+
+// Rotas do /graphql: consultas por GET/POST, GraphiQL e subscriptions via WebSocket
+pub fn routes(state: Arc<AppState>) -> Router<Arc<AppState>> {
+    let schema = state.config.graphql.schema();
+    
+    Router::new()
+        .route("/graphql", get(handle_graphql_get).post(handle_graphql_post))
+        .layer(Extension(schema))
+}
+
+// Dados da requisição HTTP disponíveis para os resolvers
+fn request_data(
+    state: &Arc<AppState>,
+    headers: &HeaderMap,
+    query: Query<HashMap<String, String>>,
+) -> Data {
+    let mut data = Data::default();
+    data.insert(state.clone());
+    data.insert(crate::extract_request_info(&state.config.redaction, headers, query, None));
+    data
+}
+
+async fn handle_graphql_get(
+    axum::extract::State(state): axum::extract::State<Arc<AppState>>,
+    Extension(schema): Extension<RustJinSchema>,
+    headers: HeaderMap,
+    uri: Uri,
+    query: Query<HashMap<String, String>>,
+    websocket: Option<WebSocketUpgrade>,
+) -> Response {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/graphql".to_string());
+    
+    if let Some(websocket) = websocket {
+        return subscribe(state.clone(), schema, &headers, query, websocket);
+    }
+    
+    // Sem consulta na URL, o navegador recebe o GraphiQL
+    if !query.contains_key("query") && !query.contains_key("extensions") {
+        state.metrics.increment_success();
+        return Html(
+            GraphiQLSource::build()
+                .endpoint("/graphql")
+                .subscription_endpoint("/graphql")
+                .title("RustJin GraphQL")
+                .finish(),
+        )
+        .into_response();
+    }
+    
+    match async_graphql::http::parse_query_string(uri.query().unwrap_or("")) {
+        Ok(request) => execute(&state, &schema, &headers, query, BatchRequest::Single(request)).await,
+        Err(e) => bad_request(&state, e.to_string()),
+    }
+}
+
+async fn handle_graphql_post(
+    axum::extract::State(state): axum::extract::State<Arc<AppState>>,
+    Extension(schema): Extension<RustJinSchema>,
+    headers: HeaderMap,
+    query: Query<HashMap<String, String>>,
+    body: Bytes,
+) -> Response {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/graphql".to_string());
+    
+    // Aceita uma operação ou um array delas (batch)
+    match serde_json::from_slice::<BatchRequest>(&body) {
+        Ok(BatchRequest::Batch(requests)) if requests.len() > MAX_BATCH => bad_request(
+            &state,
+            format!("Batch has {} operations (max: {})", requests.len(), MAX_BATCH),
+        ),
+        Ok(batch) => execute(&state, &schema, &headers, query, batch).await,
+        Err(e) => bad_request(&state, e.to_string()),
+    }
+}
+
+fn bad_request(state: &AppState, message: String) -> Response {
+    state.metrics.increment_failed();
+    (
+        StatusCode::BAD_REQUEST,
+        Json(json!({
+            "error": "Invalid GraphQL request",
+            "message": message
+        }))
+    ).into_response()
+}
+
+async fn execute(
+    state: &Arc<AppState>,
+    schema: &RustJinSchema,
+    headers: &HeaderMap,
+    query: Query<HashMap<String, String>>,
+    batch: BatchRequest,
+) -> Response {
+    let batch = batch
+        .data(state.clone())
+        .data(crate::extract_request_info(&state.config.redaction, headers, query, None));
+    
+    let response = schema.execute_batch(batch).await;
+    
+    if response.is_ok() {
+        state.metrics.increment_success();
+    } else {
+        state.metrics.increment_failed();
+    }
+    
+    Json(response).into_response()
+}
+
+// Subscriptions nos protocolos graphql-transport-ws e graphql-ws (legado)
+fn subscribe(
+    state: Arc<AppState>,
+    schema: RustJinSchema,
+    headers: &HeaderMap,
+    query: Query<HashMap<String, String>>,
+    websocket: WebSocketUpgrade,
+) -> Response {
+    let protocol = headers
+        .get("sec-websocket-protocol")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|protocols| {
+            protocols
+                .split(',')
+                .find_map(|p| p.trim().parse::<WebSocketProtocols>().ok())
+        })
+        .unwrap_or(WebSocketProtocols::GraphQLWS);
+    let data = request_data(&state, headers, query);
+    state.metrics.increment_success();
+    
+    websocket
+        .protocols(ALL_WEBSOCKET_PROTOCOLS)
+        .on_upgrade(move |socket| async move {
+            let (mut sink, stream) = socket.split();
+            
+            let input = stream
+                .take_while(|message| future::ready(message.is_ok()))
+                .filter_map(|message| {
+                    future::ready(match message {
+                        Ok(Message::Text(text)) => Some(text.into_bytes()),
+                        Ok(Message::Binary(bytes)) => Some(bytes),
+                        _ => None,
+                    })
+                });
+            
+            let mut output = WebSocket::new(schema, input, protocol).connection_data(data);
+            
+            while let Some(message) = output.next().await {
+                let message = match message {
+                    WsMessage::Text(text) => Message::Text(text),
+                    WsMessage::Close(code, reason) => Message::Close(Some(CloseFrame {
+                        code,
+                        reason: reason.into(),
+                    })),
+                };
+                if sink.send(message).await.is_err() {
+                    break;
+                }
+            }
+        })
+}
+
+// Store em memória usado pelas mutations e pela subscription itemEvents
+struct Store {
+    items: Mutex<BTreeMap<u64, Item>>,
+    next_id: AtomicU64,
+    events: broadcast::Sender<ItemEvent>,
+}
+
+impl Store {
+    // Um resolver que entrou em pânico com o lock envenena o store: vira erro GraphQL, não pânico
+    fn items(&self) -> Result<MutexGuard<'_, BTreeMap<u64, Item>>> {
+        self.items
+            .lock()
+            .map_err(|_| Error::new("Item store is unavailable"))
+    }
+
+    fn publish(&self, kind: ItemEventKind, item: &Item) {
+        // Sem assinantes o envio falha, e tudo bem
+        let _ = self.events.send(ItemEvent {
+            kind,
+            item: item.clone(),
+        });
+    }
+}
+
+#[derive(Clone, SimpleObject)]
+struct Item {
+    id: ID,
+    name: String,
+    value: Option<String>,
+    created_at: String,
+    updated_at: String,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Enum)]
+enum ItemEventKind {
+    Created,
+    Updated,
+    Deleted,
+}
+
+#[derive(Clone, SimpleObject)]
+struct ItemEvent {
+    kind: ItemEventKind,
+    item: Item,
+}
+
+#[derive(SimpleObject)]
+struct KeyValue {
+    name: String,
+    value: String,
+}
+
+#[derive(SimpleObject)]
+struct Delay {
+    seconds: u64,
+    message: String,
+}
+
+#[derive(SimpleObject)]
+struct Tick {
+    index: u32,
+    timestamp: String,
+}
+
+fn key_values(map: &HashMap<String, String>) -> Vec<KeyValue> {
+    let mut pairs: Vec<KeyValue> = map
+        .iter()
+        .map(|(name, value)| KeyValue {
+            name: name.clone(),
+            value: value.clone(),
+        })
+        .collect();
+    pairs.sort_by(|a, b| a.name.cmp(&b.name));
+    pairs
+}
+
+fn check_length(field: &str, value: &str) -> Result<()> {
+    if value.len() > MAX_FIELD_LENGTH {
+        return Err(Error::new(format!(
+            "Field '{}' exceeds {} bytes",
+            field, MAX_FIELD_LENGTH
+        )));
+    }
+    Ok(())
+}
+
+fn parse_id(id: &ID) -> Result<u64> {
+    id.parse().map_err(|_| Error::new(format!("Invalid id: '{}'", id.as_str())))
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    // Headers da requisição HTTP (ou do upgrade do WebSocket), já com segredos ocultados
+    async fn headers(&self, ctx: &Context<'_>) -> Result<Vec<KeyValue>> {
+        Ok(key_values(&ctx.data::<RequestInfo>()?.headers))
+    }
+
+    async fn header(&self, ctx: &Context<'_>, name: String) -> Result<Option<String>> {
+        Ok(ctx
+            .data::<RequestInfo>()?
+            .headers
+            .get(&name.to_lowercase())
+            .cloned())
+    }
+
+    // Parâmetros da query string de /graphql
+    async fn args(&self, ctx: &Context<'_>) -> Result<Vec<KeyValue>> {
+        Ok(key_values(&ctx.data::<RequestInfo>()?.args))
+    }
+
+    async fn origin(&self, ctx: &Context<'_>) -> Result<String> {
+        Ok(ctx.data::<RequestInfo>()?.origin.clone())
+    }
+
+    async fn uuid(&self) -> String {
+        uuid::Uuid::new_v4().to_string()
+    }
+
+    async fn echo(&self, message: String) -> String {
+        message
+    }
+
+    // Equivalente do /delay/:seconds
+    async fn delay(&self, ctx: &Context<'_>, seconds: u64) -> Result<Delay> {
+        if seconds > MAX_DELAY {
+            if let Ok(state) = ctx.data::<Arc<AppState>>() {
+                state.metrics.increment_delays_blocked();
+            }
+            tracing::warn!("⚠️  Delay GraphQL bloqueado: {} segundos (max: {})", seconds, MAX_DELAY);
+            return Err(Error::new(format!("Maximum delay is {} seconds", MAX_DELAY)));
+        }
+        
+        tokio::time::sleep(Duration::from_secs(seconds)).await;
+        
+        Ok(Delay {
+            seconds,
+            message: format!("Delayed for {} seconds", seconds),
+        })
+    }
+
+    async fn items(&self, ctx: &Context<'_>) -> Result<Vec<Item>> {
+        let items = ctx.data::<Store>()?.items()?;
+        Ok(items.values().cloned().collect())
+    }
+
+    async fn item(&self, ctx: &Context<'_>, id: ID) -> Result<Option<Item>> {
+        let store = ctx.data::<Store>()?;
+        let id = parse_id(&id)?;
+        let items = store.items()?;
+        Ok(items.get(&id).cloned())
+    }
+}
+
+pub struct MutationRoot;
+
+#[Object]
+impl MutationRoot {
+    async fn create_item(
+        &self,
+        ctx: &Context<'_>,
+        name: String,
+        value: Option<String>,
+    ) -> Result<Item> {
+        check_length("name", &name)?;
+        if let Some(value) = &value {
+            check_length("value", value)?;
+        }
+        
+        let store = ctx.data::<Store>()?;
+        let mut items = store.items()?;
+        if items.len() >= MAX_ITEMS {
+            return Err(Error::new(format!("Store is full (max: {} items)", MAX_ITEMS)));
+        }
+        
+        let id = store.next_id.fetch_add(1, Ordering::Relaxed);
+        let now = chrono::Utc::now().to_rfc3339();
+        let item = Item {
+            id: ID::from(id.to_string()),
+            name,
+            value,
+            created_at: now.clone(),
+            updated_at: now,
+        };
+        items.insert(id, item.clone());
+        store.publish(ItemEventKind::Created, &item);
+        
+        Ok(item)
+    }
+
+    async fn update_item(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+        name: Option<String>,
+        value: Option<String>,
+    ) -> Result<Item> {
+        let store = ctx.data::<Store>()?;
+        let key = parse_id(&id)?;
+        let mut items = store.items()?;
+        let item = items
+            .get_mut(&key)
+            .ok_or_else(|| Error::new(format!("Item '{}' not found", id.as_str())))?;
+        
+        if let Some(name) = name {
+            check_length("name", &name)?;
+            item.name = name;
+        }
+        if let Some(value) = value {
+            check_length("value", &value)?;
+            item.value = Some(value);
+        }
+        item.updated_at = chrono::Utc::now().to_rfc3339();
+        
+        let item = item.clone();
+        store.publish(ItemEventKind::Updated, &item);
+        Ok(item)
+    }
+
+    async fn delete_item(&self, ctx: &Context<'_>, id: ID) -> Result<bool> {
+        let store = ctx.data::<Store>()?;
+        let key = parse_id(&id)?;
+        let removed = store.items()?.remove(&key);
+        
+        match removed {
+            Some(item) => {
+                store.publish(ItemEventKind::Deleted, &item);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+pub struct SubscriptionRoot;
+
+#[Subscription]
+impl SubscriptionRoot {
+    // Alterações feitas pelas mutations, de qualquer cliente
+    async fn item_events(&self, ctx: &Context<'_>) -> Result<impl Stream<Item = ItemEvent>> {
+        let receiver = ctx.data::<Store>()?.events.subscribe();
+        
+        // Eventos perdidos por um assinante lento são descartados
+        Ok(BroadcastStream::new(receiver).filter_map(|event| future::ready(event.ok())))
+    }
+
+    // Equivalente do /stream/:n: `count` eventos a cada `interval_ms`
+    async fn ticks(
+        &self,
+        #[graphql(default = 5)] count: u32,
+        #[graphql(default = 1000)] interval_ms: u64,
+    ) -> Result<impl Stream<Item = Tick>> {
+        if count > MAX_TICKS {
+            return Err(Error::new(format!("Maximum count is {}", MAX_TICKS)));
+        }
+        if !(10..=MAX_DELAY * 1000).contains(&interval_ms) {
+            return Err(Error::new(format!(
+                "intervalMs must be between 10 and {}",
+                MAX_DELAY * 1000
+            )));
+        }
+        
+        let interval = tokio::time::interval(Duration::from_millis(interval_ms));
+        
+        Ok(IntervalStream::new(interval)
+            .take(count as usize)
+            .enumerate()
+            .map(|(index, _)| Tick {
+                index: index as u32,
+                timestamp: chrono::Utc::now().to_rfc3339(),
+            }))
+    }
+}
@@ -0,0 +1,378 @@
+use axum::Router;
+use std::{collections::HashMap, pin::Pin, sync::Arc, time::Duration};
+use tokio::{sync::mpsc, time::Instant};
+use tokio_stream::{wrappers::ReceiverStream, Stream};
+use tonic::{
+    metadata::{KeyAndValueRef, MetadataMap},
+    Code, Request, Response, Status, Streaming,
+};
+use tonic_web::GrpcWebLayer;
+use tower::Layer;
+
+use crate::AppState;
+
+pub mod pb {
+    tonic::include_proto!("rustjin.echo.v1");
+
+    pub const FILE_DESCRIPTOR_SET: &[u8] = tonic::include_file_descriptor_set!("echo_descriptor");
+}
+
+use pb::{
+    echo_service_server::{EchoServiceServer, SERVICE_NAME},
+    EchoRequest, EchoResponse, StreamRequest,
+};
+
+// Mesmos limites de /delay/:seconds e /stream/:n
+const MAX_DELAY_MS: u32 = 10_000;
+const MAX_STREAM_MESSAGES: u32 = 100;
+
+// Metadata da requisição com este prefixo volta como metadata da resposta
+const ECHO_METADATA_PREFIX: &str = "x-echo-";
+
+type EchoStream = Pin<Box<dyn Stream<Item = Result<EchoResponse, Status>> + Send>>;
+
+// Rotas gRPC (e gRPC-Web) montadas no mesmo Router do HTTP
+pub fn routes(state: Arc<AppState>) -> Router<Arc<AppState>> {
+    let web = GrpcWebLayer::new();
+    
+    // grpcurl e outros clientes ainda usam a versão v1alpha da reflection
+    let reflection = || {
+        tonic_reflection::server::Builder::configure()
+            .register_encoded_file_descriptor_set(pb::FILE_DESCRIPTOR_SET)
+    };
+    let reflection_v1 = reflection()
+        .build_v1()
+        .expect("descritor gerado pelo build.rs é válido");
+    let reflection_v1alpha = reflection()
+        .build_v1alpha()
+        .expect("descritor gerado pelo build.rs é válido");
+    
+    Router::new()
+        .route_service(
+            &format!("/{}/*method", SERVICE_NAME),
+            web.layer(EchoServiceServer::new(EchoService { state })),
+        )
+        .route_service(
+            "/grpc.reflection.v1.ServerReflection/*method",
+            web.layer(reflection_v1),
+        )
+        .route_service(
+            "/grpc.reflection.v1alpha.ServerReflection/*method",
+            web.layer(reflection_v1alpha),
+        )
+}
+
+struct EchoService {
+    state: Arc<AppState>,
+}
+
+impl EchoService {
+    fn record(&self, method: &str) {
+        self.state.metrics.increment_total();
+        self.state
+            .metrics
+            .record_endpoint(format!("/{}/{}", SERVICE_NAME, method));
+    }
+
+    fn finish<T>(&self, result: Result<T, Status>) -> Result<T, Status> {
+        finish(&self.state, result)
+    }
+
+    // Metadata da requisição, com os mesmos valores ocultados que os headers do eco HTTP
+    fn metadata(&self, metadata: &MetadataMap) -> HashMap<String, String> {
+        let mut map: HashMap<String, String> = metadata
+            .iter()
+            .map(|entry| match entry {
+                KeyAndValueRef::Ascii(key, value) => (
+                    key.to_string(),
+                    value.to_str().unwrap_or("").to_string(),
+                ),
+                KeyAndValueRef::Binary(key, value) => (
+                    key.to_string(),
+                    String::from_utf8_lossy(value.as_encoded_bytes()).to_string(),
+                ),
+            })
+            .collect();
+        self.state.config.redaction.headers(&mut map);
+        map
+    }
+}
+
+fn finish<T>(state: &AppState, result: Result<T, Status>) -> Result<T, Status> {
+    match &result {
+        Ok(_) => state.metrics.increment_success(),
+        Err(status) => {
+            state.metrics.increment_failed();
+            tracing::warn!("🚫 gRPC encerrado com status {:?}: {}", status.code(), status.message());
+        }
+    }
+    result
+}
+
+// Equivalente gRPC do /status/:code
+fn requested_status(code: u32, message: &str) -> Result<(), Status> {
+    match code {
+        0 => Ok(()),
+        1..=16 => {
+            let code = Code::from_i32(code as i32);
+            let message = if message.is_empty() {
+                code.description().to_string()
+            } else {
+                message.to_string()
+            };
+            Err(Status::new(code, message))
+        }
+        _ => Err(Status::invalid_argument(format!(
+            "Invalid status_code: {} (expected 0-16)",
+            code
+        ))),
+    }
+}
+
+// Deadline enviado pelo cliente no header grpc-timeout (ex: "500m", "2S")
+fn deadline(metadata: &MetadataMap) -> Option<Instant> {
+    let timeout = metadata.get("grpc-timeout")?.to_str().ok()?;
+    let (value, unit) = timeout.split_at(timeout.len().checked_sub(1)?);
+    let value: u64 = value.parse().ok()?;
+    
+    let timeout = match unit {
+        "H" => Duration::from_secs(value.saturating_mul(3600)),
+        "M" => Duration::from_secs(value.saturating_mul(60)),
+        "S" => Duration::from_secs(value),
+        "m" => Duration::from_millis(value),
+        "u" => Duration::from_micros(value),
+        "n" => Duration::from_nanos(value),
+        _ => return None,
+    };
+    Instant::now().checked_add(timeout)
+}
+
+// Equivalente gRPC do /delay/:seconds; respeita o deadline do cliente
+async fn delay(state: &AppState, delay_ms: u32, deadline: Option<Instant>) -> Result<(), Status> {
+    if delay_ms > MAX_DELAY_MS {
+        state.metrics.increment_delays_blocked();
+        tracing::warn!("⚠️  Delay gRPC bloqueado: {} ms (max: {})", delay_ms, MAX_DELAY_MS);
+        return Err(Status::invalid_argument(format!(
+            "Maximum delay is {} ms",
+            MAX_DELAY_MS
+        )));
+    }
+    
+    let until = Instant::now() + Duration::from_millis(delay_ms.into());
+    match deadline {
+        Some(deadline) if deadline < until => {
+            tokio::time::sleep_until(deadline).await;
+            Err(Status::deadline_exceeded("Deadline exceeded before the delay finished"))
+        }
+        _ => {
+            tokio::time::sleep_until(until).await;
+            Ok(())
+        }
+    }
+}
+
+// Copia a metadata "x-echo-*" da requisição para a resposta
+fn echo_metadata<T>(request: &MetadataMap, mut response: Response<T>) -> Response<T> {
+    for entry in request.iter() {
+        if let KeyAndValueRef::Ascii(key, value) = entry {
+            if key.as_str().starts_with(ECHO_METADATA_PREFIX) {
+                response.metadata_mut().insert(key.clone(), value.clone());
+            }
+        }
+    }
+    response
+}
+
+#[tonic::async_trait]
+impl pb::echo_service_server::EchoService for EchoService {
+    async fn unary_echo(
+        &self,
+        request: Request<EchoRequest>,
+    ) -> Result<Response<EchoResponse>, Status> {
+        self.record("UnaryEcho");
+        
+        let deadline = deadline(request.metadata());
+        let metadata = self.metadata(request.metadata());
+        let echo = request.get_ref();
+        
+        let result = async {
+            delay(&self.state, echo.delay_ms, deadline).await?;
+            requested_status(echo.status_code, &echo.status_message)?;
+            
+            Ok(echo_metadata(
+                request.metadata(),
+                Response::new(EchoResponse {
+                    message: echo.message.clone(),
+                    metadata,
+                    index: 0,
+                    received: 1,
+                }),
+            ))
+        }
+        .await;
+        
+        self.finish(result)
+    }
+
+    type ServerStreamingEchoStream = EchoStream;
+
+    async fn server_streaming_echo(
+        &self,
+        request: Request<StreamRequest>,
+    ) -> Result<Response<Self::ServerStreamingEchoStream>, Status> {
+        self.record("ServerStreamingEcho");
+        
+        let stream_request = request.get_ref();
+        if stream_request.count > MAX_STREAM_MESSAGES {
+            tracing::warn!(
+                "🚫 Stream gRPC bloqueado: {} mensagens (max: {})",
+                stream_request.count,
+                MAX_STREAM_MESSAGES
+            );
+            return self.finish(Err(Status::invalid_argument(format!(
+                "Maximum count is {}",
+                MAX_STREAM_MESSAGES
+            ))));
+        }
+        
+        let deadline = deadline(request.metadata());
+        let metadata = self.metadata(request.metadata());
+        let StreamRequest {
+            message,
+            count,
+            interval_ms,
+            status_code,
+            status_message,
+        } = stream_request.clone();
+        let state = self.state.clone();
+        let (tx, rx) = mpsc::channel(4);
+        
+        tokio::spawn(async move {
+            let result = async {
+                for index in 0..count {
+                    if index > 0 {
+                        delay(&state, interval_ms, deadline).await?;
+                    }
+                    
+                    let reply = EchoResponse {
+                        message: message.clone(),
+                        metadata: metadata.clone(),
+                        index,
+                        received: 1,
+                    };
+                    // Cliente desconectou: não há para quem enviar o restante
+                    if tx.send(Ok(reply)).await.is_err() {
+                        return Ok(());
+                    }
+                }
+                requested_status(status_code, &status_message)
+            }
+            .await;
+            
+            if let Err(status) = finish(&state, result) {
+                let _ = tx.send(Err(status)).await;
+            }
+        });
+        
+        Ok(echo_metadata(
+            request.metadata(),
+            Response::new(Box::pin(ReceiverStream::new(rx)) as EchoStream),
+        ))
+    }
+
+    async fn client_streaming_echo(
+        &self,
+        request: Request<Streaming<EchoRequest>>,
+    ) -> Result<Response<EchoResponse>, Status> {
+        self.record("ClientStreamingEcho");
+        
+        let deadline = deadline(request.metadata());
+        let metadata = self.metadata(request.metadata());
+        let request_metadata = request.metadata().clone();
+        let mut stream = request.into_inner();
+        
+        let result = async {
+            let mut messages = Vec::new();
+            
+            while let Some(echo) = stream.message().await? {
+                // Tudo fica em memória até o fim do stream: o total de mensagens é limitado
+                if messages.len() >= MAX_STREAM_MESSAGES as usize {
+                    tracing::warn!(
+                        "🚫 Stream gRPC do cliente bloqueado: mais de {} mensagens",
+                        MAX_STREAM_MESSAGES
+                    );
+                    return Err(Status::resource_exhausted(format!(
+                        "Maximum {} messages per stream",
+                        MAX_STREAM_MESSAGES
+                    )));
+                }
+                
+                delay(&self.state, echo.delay_ms, deadline).await?;
+                requested_status(echo.status_code, &echo.status_message)?;
+                messages.push(echo.message);
+            }
+            
+            Ok(echo_metadata(
+                &request_metadata,
+                Response::new(EchoResponse {
+                    received: messages.len() as u32,
+                    message: messages.join(" "),
+                    metadata,
+                    index: 0,
+                }),
+            ))
+        }
+        .await;
+        
+        self.finish(result)
+    }
+
+    type BidiStreamingEchoStream = EchoStream;
+
+    async fn bidi_streaming_echo(
+        &self,
+        request: Request<Streaming<EchoRequest>>,
+    ) -> Result<Response<Self::BidiStreamingEchoStream>, Status> {
+        self.record("BidiStreamingEcho");
+        
+        let deadline = deadline(request.metadata());
+        let metadata = self.metadata(request.metadata());
+        let request_metadata = request.metadata().clone();
+        let mut stream = request.into_inner();
+        let state = self.state.clone();
+        let (tx, rx) = mpsc::channel(4);
+        
+        tokio::spawn(async move {
+            let result = async {
+                let mut index = 0;
+                
+                while let Some(echo) = stream.message().await? {
+                    delay(&state, echo.delay_ms, deadline).await?;
+                    requested_status(echo.status_code, &echo.status_message)?;
+                    
+                    let reply = EchoResponse {
+                        message: echo.message,
+                        metadata: metadata.clone(),
+                        index,
+                        received: index + 1,
+                    };
+                    if tx.send(Ok(reply)).await.is_err() {
+                        break;
+                    }
+                    index += 1;
+                }
+                Ok(())
+            }
+            .await;
+            
+            if let Err(status) = finish(&state, result) {
+                let _ = tx.send(Err(status)).await;
+            }
+        });
+        
+        Ok(echo_metadata(
+            &request_metadata,
+            Response::new(Box::pin(ReceiverStream::new(rx)) as EchoStream),
+        ))
+    }
+}
@@ -0,0 +1,497 @@
+use axum::{
+    body::Bytes,
+    http::{
+        header::{CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE, LOCATION},
+        HeaderMap, Method, StatusCode, Uri, Version,
+    },
+};
+use base64::{engine::general_purpose, Engine as _};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    path::Path,
+};
+
+use crate::mock::ADMIN_PREFIX;
+use crate::protocol::HOP_BY_HOP_HEADERS;
+use crate::redact::Redaction;
+use crate::RequestInfo;
+
+// Mapeamentos importados perdem para os cadastrados com a prioridade padrão
+const IMPORT_PRIORITY: u32 = 10;
+
+// Arquivo HAR 1.2: só os campos que exportamos ou que a importação usa
+#[derive(Serialize, Deserialize)]
+pub struct Har {
+    log: Log,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Log {
+    #[serde(default)]
+    version: String,
+    #[serde(default)]
+    creator: Creator,
+    #[serde(default)]
+    entries: Vec<Entry>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct Creator {
+    name: String,
+    version: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Entry {
+    #[serde(default)]
+    started_date_time: String,
+    #[serde(default)]
+    time: f64,
+    request: HarRequest,
+    response: HarResponse,
+    #[serde(default)]
+    cache: Value,
+    #[serde(default)]
+    timings: Timings,
+}
+
+#[derive(Serialize, Deserialize)]
+struct NameValue {
+    name: String,
+    value: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarRequest {
+    method: String,
+    url: String,
+    #[serde(default)]
+    http_version: String,
+    #[serde(default)]
+    cookies: Vec<Value>,
+    #[serde(default)]
+    headers: Vec<NameValue>,
+    #[serde(default)]
+    query_string: Vec<NameValue>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    post_data: Option<PostData>,
+    #[serde(default = "unknown_size")]
+    headers_size: i64,
+    #[serde(default = "unknown_size")]
+    body_size: i64,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PostData {
+    #[serde(default)]
+    mime_type: String,
+    #[serde(default)]
+    params: Vec<Value>,
+    #[serde(default)]
+    text: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HarResponse {
+    status: u16,
+    #[serde(default)]
+    status_text: String,
+    #[serde(default)]
+    http_version: String,
+    #[serde(default)]
+    cookies: Vec<Value>,
+    #[serde(default)]
+    headers: Vec<NameValue>,
+    #[serde(default)]
+    content: Content,
+    #[serde(default, rename = "redirectURL")]
+    redirect_url: String,
+    #[serde(default = "unknown_size")]
+    headers_size: i64,
+    #[serde(default = "unknown_size")]
+    body_size: i64,
+}
+
+// Pela especificação, "text" vem decodificado (sem Content-Encoding); binário vai em base64
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Content {
+    #[serde(default)]
+    size: i64,
+    #[serde(default)]
+    mime_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    encoding: Option<String>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct Timings {
+    #[serde(default)]
+    send: f64,
+    #[serde(default)]
+    wait: f64,
+    #[serde(default)]
+    receive: f64,
+}
+
+fn unknown_size() -> i64 {
+    -1
+}
+
+fn http_version(version: Version) -> String {
+    format!("{:?}", version)
+}
+
+// Lista ordenada por nome; os valores já vêm ocultados
+fn name_values(map: HashMap<String, String>) -> Vec<NameValue> {
+    map.into_iter()
+        .collect::<BTreeMap<_, _>>()
+        .into_iter()
+        .map(|(name, value)| NameValue { name, value })
+        .collect()
+}
+
+// Uma requisição do journal com a resposta que recebeu
+pub struct Exchange<'a> {
+    pub started: chrono::DateTime<chrono::Utc>,
+    pub elapsed_ms: f64,
+    pub method: &'a Method,
+    pub url: String,
+    pub version: Version,
+    // Dados de extract_request_info: headers, query e corpo já ocultados
+    pub request: RequestInfo,
+    // -1 quando o corpo não foi lido (streaming)
+    pub request_size: i64,
+    pub status: u16,
+    pub response_headers: &'a HeaderMap,
+    pub response_body: Option<&'a Bytes>,
+}
+
+impl Exchange<'_> {
+    fn to_entry(&self, redaction: &Redaction) -> Entry {
+        let request_type = self.request.headers.get(CONTENT_TYPE.as_str()).cloned().unwrap_or_default();
+        let post_data = self.request.data.clone().filter(|text| !text.is_empty()).map(|text| PostData {
+            mime_type: request_type,
+            params: Vec::new(),
+            text,
+        });
+        
+        let mut response_headers: HashMap<String, String> = self
+            .response_headers
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_str().unwrap_or("").to_string()))
+            .collect();
+        redaction.headers(&mut response_headers);
+        let header = |name: &str| response_headers.get(name).cloned().unwrap_or_default();
+        let content = match self.response_body {
+            Some(body) => content(&header(CONTENT_TYPE.as_str()), body, redaction),
+            None => Content {
+                size: -1,
+                mime_type: header(CONTENT_TYPE.as_str()),
+                ..Content::default()
+            },
+        };
+        
+        Entry {
+            started_date_time: self.started.to_rfc3339(),
+            time: self.elapsed_ms,
+            request: HarRequest {
+                method: self.method.to_string(),
+                url: redaction.url(&self.url),
+                http_version: http_version(self.version),
+                cookies: Vec::new(),
+                headers: name_values(self.request.headers.clone()),
+                query_string: name_values(self.request.args.clone()),
+                post_data,
+                headers_size: -1,
+                body_size: self.request_size,
+            },
+            response: HarResponse {
+                status: self.status,
+                status_text: StatusCode::from_u16(self.status)
+                    .ok()
+                    .and_then(|status| status.canonical_reason())
+                    .unwrap_or("")
+                    .to_string(),
+                http_version: http_version(self.version),
+                cookies: Vec::new(),
+                redirect_url: header(LOCATION.as_str()),
+                body_size: self.response_body.map(|body| body.len() as i64).unwrap_or(-1),
+                headers: name_values(response_headers),
+                content,
+                headers_size: -1,
+            },
+            cache: json!({}),
+            timings: Timings {
+                send: 0.0,
+                wait: self.elapsed_ms,
+                receive: 0.0,
+            },
+        }
+    }
+}
+
+// Texto vai como está (JSON com os segredos ocultados); binário, em base64
+fn content(mime_type: &str, body: &Bytes, redaction: &Redaction) -> Content {
+    let (text, encoding) = match std::str::from_utf8(body) {
+        Ok(text) => {
+            let redacted = serde_json::from_str::<Value>(text)
+                .ok()
+                .and_then(|mut value| redaction.json(&mut value).then(|| value.to_string()));
+            (redacted.unwrap_or_else(|| text.to_string()), None)
+        }
+        Err(_) => (general_purpose::STANDARD.encode(body), Some("base64".to_string())),
+    };
+    
+    Content {
+        size: body.len() as i64,
+        mime_type: mime_type.to_string(),
+        text: Some(text),
+        encoding,
+    }
+}
+
+// Tráfego capturado, do mais antigo para o mais recente
+pub fn export(exchanges: &[Exchange], redaction: &Redaction) -> Har {
+    Har {
+        log: Log {
+            version: "1.2".to_string(),
+            creator: Creator {
+                name: "RustJin".to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+            },
+            entries: exchanges.iter().map(|exchange| exchange.to_entry(redaction)).collect(),
+        },
+    }
+}
+
+// Headers que não fazem sentido reenviar: o corpo importado já vem decodificado
+fn importable(name: &str) -> bool {
+    let name = name.to_lowercase();
+    !HOP_BY_HOP_HEADERS.contains(&name.as_str())
+        && name != CONTENT_LENGTH.as_str()
+        && name != CONTENT_ENCODING.as_str()
+        && !name.starts_with(':')
+}
+
+impl Entry {
+    // Mapeamento no formato de /__admin/mappings que responde por método e caminho
+    fn to_mapping(&self) -> Option<(String, String, Value)> {
+        let status = self.response.status;
+        let path = self.request.url.parse::<Uri>().ok()?.path().to_string();
+        if !(200..=599).contains(&status) || !path.starts_with('/') || path.starts_with(ADMIN_PREFIX) {
+            return None;
+        }
+        let method = self.request.method.to_uppercase();
+        
+        // Headers repetidos são unidos, exceto Set-Cookie (fica o primeiro)
+        let mut headers: HashMap<String, String> = HashMap::new();
+        for header in self.response.headers.iter().filter(|h| importable(&h.name)) {
+            let name = header.name.to_lowercase();
+            // Um "{{" seria lido como expressão de template
+            if header.value.contains("{{") {
+                continue;
+            }
+            match headers.get_mut(&name) {
+                Some(_) if name == "set-cookie" => {}
+                Some(value) => {
+                    value.push_str(", ");
+                    value.push_str(&header.value);
+                }
+                None => {
+                    headers.insert(name, header.value.clone());
+                }
+            }
+        }
+        
+        let content = &self.response.content;
+        let body = match (content.text.as_deref(), content.encoding.as_deref()) {
+            (Some(text), Some("base64")) => general_purpose::STANDARD.decode(text.trim()).ok()?,
+            (Some(text), _) => text.as_bytes().to_vec(),
+            (None, _) => Vec::new(),
+        };
+        if !body.is_empty() && !headers.contains_key(CONTENT_TYPE.as_str()) && !content.mime_type.is_empty() {
+            headers.insert(CONTENT_TYPE.to_string(), content.mime_type.clone());
+        }
+        
+        // Corpo em base64 é enviado como está, sem passar pelos templates
+        let mut response = json!({
+            "status": status,
+            "headers": headers
+        });
+        if !body.is_empty() {
+            response["base64_body"] = json!(general_purpose::STANDARD.encode(&body));
+        }
+        
+        let mapping = json!({
+            "name": format!("HAR {} {}", method, path),
+            "priority": IMPORT_PRIORITY,
+            "request": {
+                "method": method,
+                "path": path
+            },
+            "response": response
+        });
+        Some((method, path, mapping))
+    }
+}
+
+// Entradas do HAR como mapeamentos; a primeira resposta de cada método e caminho vence
+pub fn import(file: &Path) -> Result<Vec<Value>, String> {
+    let raw = std::fs::read(file).map_err(|e| e.to_string())?;
+    let har: Har = serde_json::from_slice(&raw).map_err(|e| e.to_string())?;
+    
+    let mut seen = HashSet::new();
+    let mut mappings = Vec::new();
+    let mut skipped = 0;
+    for entry in &har.log.entries {
+        let Some((method, path, mapping)) = entry.to_mapping() else {
+            skipped += 1;
+            continue;
+        };
+        if seen.insert((method, path)) {
+            mappings.push(mapping);
+        } else {
+            skipped += 1;
+        }
+    }
+    
+    if skipped > 0 {
+        tracing::info!(
+            "📦 {}: {} entradas ignoradas (repetidas, sem resposta ou em {})",
+            file.display(),
+            skipped,
+            ADMIN_PREFIX
+        );
+    }
+    Ok(mappings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    fn info(headers: &[(&str, &str)], data: Option<&str>) -> RequestInfo {
+        RequestInfo {
+            method: None,
+            args: HashMap::from([("page".to_string(), "1".to_string())]),
+            headers: headers.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            origin: "127.0.0.1".to_string(),
+            url: "http://localhost:8105/users?page=1".to_string(),
+            data: data.map(str::to_string),
+            json: None,
+            form: None,
+            decode_error: None,
+        }
+    }
+
+    fn exchange<'a>(
+        method: &'a Method,
+        url: &str,
+        status: u16,
+        headers: &'a HeaderMap,
+        body: &'a Bytes,
+    ) -> Exchange<'a> {
+        Exchange {
+            started: chrono::Utc::now(),
+            elapsed_ms: 1.5,
+            method,
+            url: url.to_string(),
+            version: Version::HTTP_11,
+            request: info(&[("content-type", "application/json")], Some(r#"{"name":"ana"}"#)),
+            request_size: 14,
+            status,
+            response_headers: headers,
+            response_body: Some(body),
+        }
+    }
+
+    // Exporta, grava em disco e importa de volta como mapeamentos
+    fn round_trip(exchanges: &[Exchange]) -> Vec<Value> {
+        let har = export(exchanges, &Config::from_env().redaction);
+        let file = std::env::temp_dir().join(format!("rustjin-har-{}.json", uuid::Uuid::new_v4()));
+        std::fs::write(&file, serde_json::to_vec(&har).unwrap()).unwrap();
+        let mappings = import(&file);
+        let _ = std::fs::remove_file(&file);
+        mappings.unwrap()
+    }
+
+    #[test]
+    fn exported_har_imports_as_mappings() {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, "application/json".parse().unwrap());
+        headers.insert(CONTENT_LENGTH, "33".parse().unwrap());
+        headers.insert("x-trace", "abc".parse().unwrap());
+        headers.insert("set-cookie", "sid=1".parse().unwrap());
+        let body = Bytes::from(r#"{"id":1,"token":"secret","ok":true}"#);
+        let get = Method::GET;
+        
+        let mappings = round_trip(&[exchange(&get, "http://localhost:8105/users?page=1", 200, &headers, &body)]);
+        assert_eq!(mappings.len(), 1);
+        
+        let mapping = &mappings[0];
+        assert_eq!(mapping["request"], json!({ "method": "GET", "path": "/users" }));
+        assert_eq!(mapping["priority"], json!(IMPORT_PRIORITY));
+        let response = &mapping["response"];
+        assert_eq!(response["status"], json!(200));
+        assert_eq!(response["headers"]["content-type"], json!("application/json"));
+        assert_eq!(response["headers"]["x-trace"], json!("abc"));
+        assert_eq!(response["headers"]["set-cookie"], json!("[REDACTED]"));
+        assert!(response["headers"].get("content-length").is_none());
+        
+        // O corpo volta com os segredos ocultados na exportação
+        let decoded = general_purpose::STANDARD
+            .decode(response["base64_body"].as_str().unwrap())
+            .unwrap();
+        let decoded: Value = serde_json::from_slice(&decoded).unwrap();
+        assert_eq!(decoded, json!({ "id": 1, "token": "[REDACTED]", "ok": true }));
+    }
+
+    #[test]
+    fn binary_bodies_survive_the_round_trip() {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, "image/png".parse().unwrap());
+        let body = Bytes::from_static(&[0x89, b'P', b'N', b'G', 0xff, 0x00]);
+        let get = Method::GET;
+        
+        let mappings = round_trip(&[exchange(&get, "http://localhost:8105/image", 200, &headers, &body)]);
+        let encoded = mappings[0]["response"]["base64_body"].as_str().unwrap();
+        assert_eq!(general_purpose::STANDARD.decode(encoded).unwrap(), body.to_vec());
+    }
+
+    #[test]
+    fn import_keeps_first_entry_and_skips_admin_paths() {
+        let headers = HeaderMap::new();
+        let first = Bytes::from("first");
+        let second = Bytes::from("second");
+        let (get, post) = (Method::GET, Method::POST);
+        
+        let mappings = round_trip(&[
+            exchange(&get, "http://localhost:8105/items", 200, &headers, &first),
+            exchange(&get, "http://localhost:8105/items?page=2", 500, &headers, &second),
+            exchange(&post, "http://localhost:8105/items", 201, &headers, &second),
+            exchange(&get, "http://localhost:8105/__admin/mappings", 200, &headers, &first),
+        ]);
+        
+        let routes: Vec<(Value, Value, Value)> = mappings
+            .iter()
+            .map(|m| (m["request"]["method"].clone(), m["request"]["path"].clone(), m["response"]["status"].clone()))
+            .collect();
+        assert_eq!(
+            routes,
+            vec![
+                (json!("GET"), json!("/items"), json!(200)),
+                (json!("POST"), json!("/items"), json!(201)),
+            ]
+        );
+    }
+}
@@ -0,0 +1,232 @@
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{header::ALT_SVC, HeaderValue, Version},
+    middleware::Next,
+    response::Response,
+    Router,
+};
+use bytes::{Buf, Bytes};
+use http_body_util::BodyExt;
+use quinn::crypto::rustls::{HandshakeData, QuicServerConfig};
+use rustls::pki_types::CertificateDer;
+use serde::Serialize;
+use std::{
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+use tower::Service;
+
+use crate::chaos::DropConnection;
+use crate::config::{env_flag, env_or};
+use crate::protocol::{self, ConnectionRequest};
+use crate::tls::{TlsInfo, TlsSetup};
+
+type RequestStream<S> = h3::server::RequestStream<S, Bytes>;
+
+// Listener HTTP/3 (QUIC, UDP) com o mesmo certificado do HTTPS
+#[derive(Clone, Serialize)]
+pub struct Http3Config {
+    pub enabled: bool,
+    pub port: u16,
+    pub max_concurrent_streams: u32,
+}
+
+impl Http3Config {
+    pub fn from_env() -> Self {
+        Self {
+            enabled: env_flag("RUSTJIN_H3"),
+            port: env_or("RUSTJIN_H3_PORT", "8443").parse().unwrap_or(8443),
+            max_concurrent_streams: env_or("RUSTJIN_H3_MAX_CONCURRENT_STREAMS", "100")
+                .parse()
+                .unwrap_or(100),
+        }
+    }
+
+    // Valor do Alt-Svc que anuncia o HTTP/3 nas respostas HTTP/1.1 e HTTP/2
+    pub fn alt_svc(&self) -> HeaderValue {
+        HeaderValue::from_str(&format!("h3=\":{}\"; ma=86400", self.port))
+            .expect("porta sempre gera um header válido")
+    }
+}
+
+pub fn endpoint(config: &Http3Config, setup: &TlsSetup) -> Result<quinn::Endpoint, String> {
+    // Mesma configuração do HTTPS (inclusive mTLS), só com o ALPN do HTTP/3
+    let mut tls = (*setup.server_config).clone();
+    tls.alpn_protocols = vec![b"h3".to_vec()];
+    let crypto = QuicServerConfig::try_from(tls).map_err(|e| e.to_string())?;
+    
+    let mut transport = quinn::TransportConfig::default();
+    transport.max_concurrent_bidi_streams(config.max_concurrent_streams.into());
+    
+    let mut server_config = quinn::ServerConfig::with_crypto(Arc::new(crypto));
+    server_config.transport_config(Arc::new(transport));
+    
+    quinn::Endpoint::server(server_config, SocketAddr::from(([0, 0, 0, 0], config.port)))
+        .map_err(|e| e.to_string())
+}
+
+pub async fn serve(endpoint: quinn::Endpoint, app: Router) {
+    while let Some(incoming) = endpoint.accept().await {
+        let app = app.clone();
+        
+        tokio::spawn(async move {
+            let connection = match incoming.await {
+                Ok(connection) => connection,
+                Err(e) => {
+                    tracing::debug!("⚡ Handshake QUIC falhou: {}", e);
+                    return;
+                }
+            };
+            serve_connection(connection, app).await;
+        });
+    }
+}
+
+fn tls_info(connection: &quinn::Connection) -> TlsInfo {
+    let handshake = connection
+        .handshake_data()
+        .and_then(|data| data.downcast::<HandshakeData>().ok());
+    let client_chain = connection
+        .peer_identity()
+        .and_then(|identity| identity.downcast::<Vec<CertificateDer<'static>>>().ok())
+        .map(|chain| *chain)
+        .unwrap_or_default();
+    
+    TlsInfo {
+        // QUIC sempre usa TLS 1.3; o quinn não expõe a cipher suite negociada
+        protocol: "TLSv1.3".to_string(),
+        cipher_suite: "unknown".to_string(),
+        alpn: handshake
+            .as_ref()
+            .and_then(|h| h.protocol.as_ref())
+            .map(|alpn| String::from_utf8_lossy(alpn).to_string()),
+        sni: handshake.and_then(|h| h.server_name),
+        client_certificate: !client_chain.is_empty(),
+        client_chain,
+    }
+}
+
+async fn serve_connection(connection: quinn::Connection, app: Router) {
+    let peer = connection.remote_address();
+    let tls = tls_info(&connection);
+    let connection_id = protocol::next_connection_id();
+    let requests = Arc::new(AtomicU64::new(0));
+    
+    let mut h3 = match h3::server::builder()
+        .build(h3_quinn::Connection::new(connection))
+        .await
+    {
+        Ok(h3) => h3,
+        Err(e) => {
+            tracing::debug!("⚡ Conexão HTTP/3 recusada ({}): {}", peer, e);
+            return;
+        }
+    };
+    
+    loop {
+        let resolver = match h3.accept().await {
+            Ok(Some(resolver)) => resolver,
+            Ok(None) => break,
+            Err(e) => {
+                tracing::debug!("⚡ Conexão HTTP/3 encerrada ({}): {}", peer, e);
+                break;
+            }
+        };
+        
+        let app = app.clone();
+        let tls = tls.clone();
+        let request_number = requests.fetch_add(1, Ordering::Relaxed) + 1;
+        
+        tokio::spawn(async move {
+            let (request, stream) = match resolver.resolve_request().await {
+                Ok(resolved) => resolved,
+                Err(e) => {
+                    tracing::debug!("⚡ Requisição HTTP/3 inválida ({}): {}", peer, e);
+                    return;
+                }
+            };
+            
+            let stream_id = stream.id().into_inner();
+            let (mut send, recv) = stream.split();
+            
+            let mut request = request.map(|()| request_body(recv));
+            request.extensions_mut().insert(tls);
+            request
+                .extensions_mut()
+                .insert(ConnectionRequest::quic(connection_id, request_number, stream_id));
+            
+            // Router está sempre pronto: poll_ready não precisa ser aguardado
+            let mut app = app;
+            let Ok(response) = app.call(request).await;
+            
+            if response.extensions().get::<DropConnection>().is_some() {
+                send.stop_stream(h3::error::Code::H3_INTERNAL_ERROR);
+                return;
+            }
+            
+            if let Err(e) = send_response(send, response).await {
+                tracing::debug!("⚡ Falha ao enviar resposta HTTP/3 ({}): {}", peer, e);
+            }
+        });
+    }
+}
+
+// Corpo da requisição lido do stream QUIC sob demanda
+fn request_body<S>(recv: RequestStream<S>) -> Body
+where
+    S: h3::quic::RecvStream + Send + 'static,
+{
+    let chunks = futures_util::stream::unfold(recv, |mut recv| async move {
+        match recv.recv_data().await {
+            Ok(Some(mut chunk)) => {
+                let bytes = chunk.copy_to_bytes(chunk.remaining());
+                Some((Ok::<_, h3::error::StreamError>(bytes), recv))
+            }
+            Ok(None) => None,
+            Err(e) => Some((Err(e), recv)),
+        }
+    });
+    Body::from_stream(chunks)
+}
+
+async fn send_response<S>(mut send: RequestStream<S>, response: Response) -> Result<(), String>
+where
+    S: h3::quic::SendStream<Bytes>,
+{
+    let (parts, mut body) = response.into_parts();
+    send.send_response(Response::from_parts(parts, ()))
+        .await
+        .map_err(|e| e.to_string())?;
+    
+    while let Some(frame) = body.frame().await {
+        let frame = frame.map_err(|e| e.to_string())?;
+        match frame.into_data() {
+            Ok(data) => send.send_data(data).await.map_err(|e| e.to_string())?,
+            Err(frame) => {
+                if let Ok(trailers) = frame.into_trailers() {
+                    send.send_trailers(trailers).await.map_err(|e| e.to_string())?;
+                }
+            }
+        }
+    }
+    
+    send.finish().await.map_err(|e| e.to_string())
+}
+
+// Anuncia o HTTP/3 em todas as respostas enviadas por TCP
+pub async fn advertise(
+    State(alt_svc): State<HeaderValue>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let over_quic = request.version() == Version::HTTP_3;
+    let mut response = next.run(request).await;
+    if !over_quic {
+        response.headers_mut().insert(ALT_SVC, alt_svc);
+    }
+    response
+}
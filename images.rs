@@ -0,0 +1,285 @@
+use image::{DynamicImage, ImageFormat as EncodedFormat, Rgb, RgbImage};
+use std::io::Cursor;
+
+pub const MAX_DIMENSION: u32 = 2048;
+pub const DEFAULT_SIZE: u32 = 200;
+const DEFAULT_COLOR: [u8; 3] = [0x34, 0x98, 0xdb];
+const DEFAULT_TEXT: &str = "RustJin";
+pub const MAX_TEXT_LENGTH: usize = 64;
+
+// Tipos oferecidos por /image, na ordem de preferência usada em empates de q
+pub const MEDIA_TYPES: [&str; 5] = [
+    "image/png",
+    "image/jpeg",
+    "image/webp",
+    "image/gif",
+    "image/svg+xml",
+];
+
+#[derive(Clone, Copy)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Webp,
+    Gif,
+    Svg,
+}
+
+impl ImageFormat {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "png" => Some(Self::Png),
+            "jpeg" | "jpg" => Some(Self::Jpeg),
+            "webp" => Some(Self::Webp),
+            "gif" => Some(Self::Gif),
+            "svg" => Some(Self::Svg),
+            _ => None,
+        }
+    }
+
+    pub fn from_media_type(media_type: &str) -> Option<Self> {
+        MEDIA_TYPES
+            .iter()
+            .position(|t| *t == media_type)
+            .map(|i| [Self::Png, Self::Jpeg, Self::Webp, Self::Gif, Self::Svg][i])
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Jpeg => "image/jpeg",
+            Self::Webp => "image/webp",
+            Self::Gif => "image/gif",
+            Self::Svg => "image/svg+xml",
+        }
+    }
+}
+
+// Parâmetros da imagem gerada; a mesma entrada sempre produz os mesmos bytes
+pub struct ImageSpec {
+    width: u32,
+    height: u32,
+    color: [u8; 3],
+    text: String,
+}
+
+impl ImageSpec {
+    pub fn new(
+        width: Option<u32>,
+        height: Option<u32>,
+        color: Option<&str>,
+        text: Option<&str>,
+    ) -> Result<Self, String> {
+        let width = width.unwrap_or(DEFAULT_SIZE);
+        let height = height.unwrap_or(DEFAULT_SIZE);
+        
+        if width == 0 || height == 0 || width > MAX_DIMENSION || height > MAX_DIMENSION {
+            return Err(format!(
+                "Width and height must be between 1 and {} pixels",
+                MAX_DIMENSION
+            ));
+        }
+        
+        let color = match color {
+            Some(color) => parse_color(color).ok_or_else(|| format!("Invalid color: '{}'", color))?,
+            None => DEFAULT_COLOR,
+        };
+        
+        let text = text.unwrap_or(DEFAULT_TEXT);
+        if text.chars().count() > MAX_TEXT_LENGTH {
+            return Err(format!("Text must be at most {} characters", MAX_TEXT_LENGTH));
+        }
+        
+        Ok(Self {
+            width,
+            height,
+            color,
+            text: text.to_string(),
+        })
+    }
+
+    // Texto em preto ou branco, o que tiver mais contraste com o fundo
+    fn text_color(&self) -> [u8; 3] {
+        let [r, g, b] = self.color.map(u32::from);
+        if (299 * r + 587 * g + 114 * b) / 1000 > 140 {
+            [0, 0, 0]
+        } else {
+            [255, 255, 255]
+        }
+    }
+}
+
+pub fn render(format: ImageFormat, spec: &ImageSpec) -> Result<Vec<u8>, String> {
+    let encoded = match format {
+        ImageFormat::Svg => return Ok(render_svg(spec).into_bytes()),
+        ImageFormat::Png => EncodedFormat::Png,
+        ImageFormat::Jpeg => EncodedFormat::Jpeg,
+        ImageFormat::Webp => EncodedFormat::WebP,
+        ImageFormat::Gif => EncodedFormat::Gif,
+    };
+    
+    let image = DynamicImage::ImageRgb8(render_raster(spec));
+    // O encoder de GIF do crate image só aceita RGBA
+    let image = match format {
+        ImageFormat::Gif => DynamicImage::ImageRgba8(image.to_rgba8()),
+        _ => image,
+    };
+    
+    let mut bytes = Cursor::new(Vec::new());
+    image
+        .write_to(&mut bytes, encoded)
+        .map_err(|e| e.to_string())?;
+    Ok(bytes.into_inner())
+}
+
+fn render_raster(spec: &ImageSpec) -> RgbImage {
+    let mut image = RgbImage::from_pixel(spec.width, spec.height, Rgb(spec.color));
+    let text: Vec<char> = spec.text.chars().collect();
+    let ink = Rgb(spec.text_color());
+    
+    // Cada caractere ocupa 6x8 células (glifo 5x7 mais espaçamento)
+    let max_chars = ((spec.width * 9 / 10) / 6).max(1) as usize;
+    let text = &text[..text.len().min(max_chars)];
+    if text.is_empty() {
+        return image;
+    }
+    
+    let scale = ((spec.width * 9 / 10) / (text.len() as u32 * 6))
+        .min((spec.height / 2) / 8)
+        .max(1);
+    let text_width = text.len() as u32 * 6 * scale - scale;
+    let x0 = spec.width.saturating_sub(text_width) / 2;
+    let y0 = spec.height.saturating_sub(7 * scale) / 2;
+    
+    for (i, c) in text.iter().enumerate() {
+        let glyph = glyph(*c);
+        let gx = x0 + i as u32 * 6 * scale;
+        
+        for (row, bits) in glyph.iter().enumerate() {
+            for col in 0..5 {
+                if bits & (0b10000 >> col) == 0 {
+                    continue;
+                }
+                for dy in 0..scale {
+                    for dx in 0..scale {
+                        let x = gx + col * scale + dx;
+                        let y = y0 + row as u32 * scale + dy;
+                        if x < spec.width && y < spec.height {
+                            image.put_pixel(x, y, ink);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    
+    image
+}
+
+fn render_svg(spec: &ImageSpec) -> String {
+    let [r, g, b] = spec.color;
+    let [tr, tg, tb] = spec.text_color();
+    let font_size = (spec.height / 8).clamp(8, 64);
+    
+    format!(
+        r##"<svg width="{w}" height="{h}" viewBox="0 0 {w} {h}" xmlns="http://www.w3.org/2000/svg">
+    <rect width="{w}" height="{h}" fill="#{r:02x}{g:02x}{b:02x}"/>
+    <text x="50%" y="50%" dominant-baseline="middle" text-anchor="middle" fill="#{tr:02x}{tg:02x}{tb:02x}" font-family="monospace" font-size="{font_size}">{text}</text>
+</svg>"##,
+        w = spec.width,
+        h = spec.height,
+        text = escape_xml(&spec.text),
+    )
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+// Aceita "#rrggbb", "rrggbb", "#rgb" ou alguns nomes comuns
+fn parse_color(color: &str) -> Option<[u8; 3]> {
+    let named = match color.to_lowercase().as_str() {
+        "black" => Some([0, 0, 0]),
+        "white" => Some([255, 255, 255]),
+        "red" => Some([255, 0, 0]),
+        "green" => Some([0, 128, 0]),
+        "blue" => Some([0, 0, 255]),
+        "yellow" => Some([255, 255, 0]),
+        "gray" | "grey" => Some([128, 128, 128]),
+        "orange" => Some([255, 165, 0]),
+        "purple" => Some([128, 0, 128]),
+        _ => None,
+    };
+    if named.is_some() {
+        return named;
+    }
+    
+    let hex = color.strip_prefix('#').unwrap_or(color);
+    let hex = match hex.len() {
+        3 => hex.chars().flat_map(|c| [c, c]).collect::<String>(),
+        6 => hex.to_string(),
+        _ => return None,
+    };
+    
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+// Fonte bitmap 5x7: cada linha usa os 5 bits menos significativos, da esquerda para a direita
+fn glyph(c: char) -> [u8; 7] {
+    match c.to_ascii_uppercase() {
+        'A' => [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'B' => [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E],
+        'C' => [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E],
+        'D' => [0x1E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x1E],
+        'E' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],
+        'F' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],
+        'G' => [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F],
+        'H' => [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'I' => [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        'J' => [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C],
+        'K' => [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+        'L' => [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F],
+        'M' => [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'O' => [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'P' => [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10],
+        'Q' => [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D],
+        'R' => [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11],
+        'S' => [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E],
+        'T' => [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'U' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'V' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04],
+        'W' => [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A],
+        'X' => [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11],
+        'Y' => [0x11, 0x11, 0x0A, 0x04, 0x04, 0x04, 0x04],
+        'Z' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F],
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        ' ' => [0x00; 7],
+        '-' => [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],
+        '_' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1F],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+        ',' => [0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08],
+        ':' => [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],
+        '!' => [0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x04],
+        '/' => [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00],
+        '+' => [0x00, 0x04, 0x04, 0x1F, 0x04, 0x04, 0x00],
+        '=' => [0x00, 0x00, 0x1F, 0x00, 0x1F, 0x00, 0x00],
+        '#' => [0x0A, 0x0A, 0x1F, 0x0A, 0x1F, 0x0A, 0x0A],
+        // Qualquer outro caractere vira '?'
+        _ => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04],
+    }
+}
@@ -0,0 +1,329 @@
+use axum::{
+    body::{Body, Bytes, HttpBody},
+    extract::{MatchedPath, Query, Request, State},
+    http::{header::CONTENT_DISPOSITION, HeaderMap, Method, StatusCode, Version},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+    routing::{get, post},
+    Router,
+};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use crate::config::Config;
+use crate::har::{self, Exchange};
+use crate::mock::{MockMatch, MockRequest, RequestPattern, ADMIN_PREFIX, MAX_BODY};
+use crate::redact::Redaction;
+use crate::tls::TlsInfo;
+use crate::{extract_request_info, AppState, RequestInfo};
+
+// Requisição recebida, guardada como chegou; os segredos só são ocultados ao exibir
+#[derive(Clone)]
+struct LoggedRequest {
+    id: String,
+    logged_at: chrono::DateTime<chrono::Utc>,
+    elapsed_ms: f64,
+    method: Method,
+    version: Version,
+    path: String,
+    query: HashMap<String, String>,
+    headers: HeaderMap,
+    // Recebida pelo listener TLS ou HTTP/3; define o esquema da URL no HAR
+    secure: bool,
+    // Corpos em streaming (sem tamanho conhecido) não são lidos
+    body: Option<Bytes>,
+    status: u16,
+    response_headers: HeaderMap,
+    // Mesma regra do corpo da requisição: respostas em streaming não são lidas
+    response_body: Option<Bytes>,
+    route: Option<String>,
+    mapping_id: Option<String>,
+}
+
+impl LoggedRequest {
+    fn unmatched(&self) -> bool {
+        self.route.is_none() && self.mapping_id.is_none()
+    }
+
+    fn matches(&self, criteria: &RequestPattern) -> bool {
+        let request = MockRequest {
+            query: self.query.clone(),
+            headers: self.headers.clone(),
+            json: self.body.as_ref().and_then(|body| serde_json::from_slice(body).ok()),
+        };
+        criteria.matches_route(&self.method, &self.path).is_some() && criteria.matches_request(&request)
+    }
+
+    fn info(&self, redaction: &Redaction) -> RequestInfo {
+        let query = Query(self.query.clone());
+        let body = self.body.clone().filter(|body| !body.is_empty());
+        let mut info = extract_request_info(redaction, &self.headers, query, body);
+        info.method = Some(self.method.to_string());
+        info
+    }
+
+    // Mesmo formato dos endpoints de eco, dentro de "request"
+    fn to_json(&self, redaction: &Redaction) -> Value {
+        let info = self.info(redaction);
+        
+        json!({
+            "id": self.id,
+            "logged_at": self.logged_at.to_rfc3339(),
+            "path": self.path,
+            "route": self.route,
+            "mapping_id": self.mapping_id,
+            "status": self.status,
+            "body_captured": self.body.is_some(),
+            "request": info
+        })
+    }
+
+    fn to_exchange(&self, config: &Config) -> Exchange<'_> {
+        let mut url = format!("{}{}", config.redirect.base_url(&self.headers, self.secure), self.path);
+        if !self.query.is_empty() {
+            url.push('?');
+            url.push_str(&serde_urlencoded::to_string(&self.query).unwrap_or_default());
+        }
+        
+        Exchange {
+            started: self.logged_at,
+            elapsed_ms: self.elapsed_ms,
+            method: &self.method,
+            url,
+            version: self.version,
+            request: self.info(&config.redaction),
+            request_size: self.body.as_ref().map(|body| body.len() as i64).unwrap_or(-1),
+            status: self.status,
+            response_headers: &self.response_headers,
+            response_body: self.response_body.as_ref(),
+        }
+    }
+}
+
+// Últimas requisições recebidas, para verificação nos testes
+#[derive(Clone)]
+pub struct Journal {
+    entries: Arc<Mutex<VecDeque<LoggedRequest>>>,
+    limit: usize,
+}
+
+impl Journal {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(VecDeque::new())),
+            limit,
+        }
+    }
+
+    fn push(&self, entry: LoggedRequest) {
+        if let Ok(mut entries) = self.entries.lock() {
+            if entries.len() >= self.limit {
+                entries.pop_front();
+            }
+            entries.push_back(entry);
+        }
+    }
+
+    // Mais recentes primeiro
+    fn filter(&self, keep: impl Fn(&LoggedRequest) -> bool) -> Vec<LoggedRequest> {
+        self.entries
+            .lock()
+            .map(|entries| entries.iter().rev().filter(|e| keep(e)).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn clear(&self) -> usize {
+        self.entries
+            .lock()
+            .map(|mut entries| entries.drain(..).count())
+            .unwrap_or(0)
+    }
+}
+
+pub async fn layer(State(state): State<Arc<AppState>>, request: Request, next: Next) -> Response {
+    let path = request.uri().path().to_string();
+    if path.starts_with(ADMIN_PREFIX) {
+        return next.run(request).await;
+    }
+    
+    let started = Instant::now();
+    let route = request.extensions().get::<MatchedPath>().map(|p| p.as_str().to_string());
+    let (parts, body) = request.into_parts();
+    let (body, captured) = capture(body).await;
+    
+    let mut entry = LoggedRequest {
+        id: uuid::Uuid::new_v4().to_string(),
+        logged_at: chrono::Utc::now(),
+        elapsed_ms: 0.0,
+        method: parts.method.clone(),
+        version: parts.version,
+        path,
+        query: parts
+            .uri
+            .query()
+            .and_then(|q| serde_urlencoded::from_str(q).ok())
+            .unwrap_or_default(),
+        headers: parts.headers.clone(),
+        secure: parts.extensions.get::<TlsInfo>().is_some(),
+        body: captured,
+        status: 0,
+        response_headers: HeaderMap::new(),
+        response_body: None,
+        route,
+        mapping_id: None,
+    };
+    
+    let (response_parts, response_body) = next.run(Request::from_parts(parts, body)).await.into_parts();
+    let (response_body, captured) = capture(response_body).await;
+    entry.elapsed_ms = started.elapsed().as_secs_f64() * 1000.0;
+    entry.status = response_parts.status.as_u16();
+    entry.response_headers = response_parts.headers.clone();
+    entry.response_body = captured;
+    entry.mapping_id = response_parts.extensions.get::<MockMatch>().map(|m| m.0.clone());
+    if entry.unmatched() {
+        tracing::debug!("🕵️  Requisição sem rota: {} {}", entry.method, entry.path);
+    }
+    state.journal.push(entry);
+    
+    Response::from_parts(response_parts, response_body)
+}
+
+// Só corpos de tamanho conhecido são lidos: streams (gRPC, uploads chunked, SSE) seguem intactos
+async fn capture(body: Body) -> (Body, Option<Bytes>) {
+    match body.size_hint().upper() {
+        Some(size) if size <= MAX_BODY as u64 => match axum::body::to_bytes(body, MAX_BODY).await {
+            Ok(bytes) => (Body::from(bytes.clone()), Some(bytes)),
+            Err(_) => (Body::empty(), None),
+        },
+        _ => (body, None),
+    }
+}
+
+pub fn routes(state: &Arc<AppState>) -> Router<Arc<AppState>> {
+    if !state.config.mocks.enabled {
+        return Router::new();
+    }
+    
+    Router::new()
+        .route("/__admin/requests", get(handle_list).delete(handle_clear))
+        .route("/__admin/requests/count", post(handle_count))
+        .route("/__admin/requests/find", post(handle_find))
+        .route("/__admin/requests/unmatched", get(handle_unmatched))
+        .route("/__admin/requests/har", get(handle_har))
+}
+
+fn listing(state: &AppState, entries: Vec<LoggedRequest>) -> Json<Value> {
+    let requests: Vec<Value> = entries
+        .iter()
+        .map(|entry| entry.to_json(&state.config.redaction))
+        .collect();
+    Json(json!({
+        "total": requests.len(),
+        "requests": requests
+    }))
+}
+
+// Critério no formato de "request" de um mapeamento; sem "path", vale qualquer caminho
+fn criteria(body: &Bytes) -> Result<RequestPattern, String> {
+    serde_json::from_slice::<Value>(body)
+        .map_err(|e| e.to_string())
+        .and_then(|mut value| {
+            if let Value::Object(map) = &mut value {
+                map.entry("path").or_insert_with(|| json!("/**"));
+            }
+            RequestPattern::parse(value)
+        })
+}
+
+fn invalid_criteria(state: &AppState, message: String) -> Response {
+    state.metrics.increment_failed();
+    (
+        StatusCode::BAD_REQUEST,
+        Json(json!({
+            "error": "Invalid request pattern",
+            "message": message
+        })),
+    )
+        .into_response()
+}
+
+#[derive(Deserialize)]
+struct ListQuery {
+    limit: Option<usize>,
+}
+
+async fn handle_list(State(state): State<Arc<AppState>>, Query(query): Query<ListQuery>) -> impl IntoResponse {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/__admin/requests".to_string());
+    state.metrics.increment_success();
+    
+    let mut entries = state.journal.filter(|_| true);
+    entries.truncate(query.limit.unwrap_or(usize::MAX));
+    listing(&state, entries)
+}
+
+async fn handle_clear(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/__admin/requests".to_string());
+    state.metrics.increment_success();
+    
+    Json(json!({ "deleted": state.journal.clear() }))
+}
+
+async fn handle_count(State(state): State<Arc<AppState>>, body: Bytes) -> Response {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/__admin/requests/count".to_string());
+    
+    let criteria = match criteria(&body) {
+        Ok(criteria) => criteria,
+        Err(message) => return invalid_criteria(&state, message),
+    };
+    
+    state.metrics.increment_success();
+    let count = state.journal.filter(|entry| entry.matches(&criteria)).len();
+    Json(json!({ "count": count })).into_response()
+}
+
+async fn handle_find(State(state): State<Arc<AppState>>, body: Bytes) -> Response {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/__admin/requests/find".to_string());
+    
+    let criteria = match criteria(&body) {
+        Ok(criteria) => criteria,
+        Err(message) => return invalid_criteria(&state, message),
+    };
+    
+    state.metrics.increment_success();
+    listing(&state, state.journal.filter(|entry| entry.matches(&criteria))).into_response()
+}
+
+// Requisições que não casaram com nenhuma rota nem com nenhum mapeamento
+async fn handle_unmatched(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/__admin/requests/unmatched".to_string());
+    state.metrics.increment_success();
+    
+    listing(&state, state.journal.filter(LoggedRequest::unmatched))
+}
+
+// Tráfego capturado em HAR 1.2, do mais antigo para o mais recente
+async fn handle_har(State(state): State<Arc<AppState>>, Query(query): Query<ListQuery>) -> impl IntoResponse {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/__admin/requests/har".to_string());
+    state.metrics.increment_success();
+    
+    let mut entries = state.journal.filter(|_| true);
+    entries.truncate(query.limit.unwrap_or(usize::MAX));
+    entries.reverse();
+    
+    let exchanges: Vec<Exchange> = entries.iter().map(|entry| entry.to_exchange(&state.config)).collect();
+    (
+        [(CONTENT_DISPOSITION, "attachment; filename=\"rustjin.har\"")],
+        Json(har::export(&exchanges, &state.config.redaction)),
+    )
+}
@@ -1,9 +1,16 @@
 use axum::{
+    body::Bytes,
     extract::{Path, Query},
-    http::{HeaderMap, StatusCode},
+    http::{
+        header::{
+            ACCEPT, ACCEPT_CHARSET, ACCEPT_LANGUAGE, CONTENT_LANGUAGE, CONTENT_TYPE, LOCATION,
+            SET_COOKIE, VARY,
+        },
+        HeaderMap, HeaderValue, Method, StatusCode,
+    },
     response::IntoResponse,
-    routing::{delete, get, patch, post, put},
-    Json, Router,
+    routing::{any, delete, get, patch, post, put},
+    Extension, Json, Router,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
@@ -19,8 +26,40 @@ use std::{
 use tower_http::cors::CorsLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+mod chaos;
+mod config;
+mod cookies;
+mod fault;
+mod formats;
+mod graphql;
+mod grpc;
+mod har;
+mod http3;
+mod images;
+mod journal;
+mod mock;
+mod negotiate;
+mod openapi;
+mod protocol;
+mod proxy;
+mod redact;
+mod session;
+mod template;
+mod tls;
+mod url_policy;
+
+use config::Config;
+use cookies::CookieAttributes;
+use redact::Redaction;
+use session::SessionStore;
+use tls::TlsInfo;
+
 // Constantes de segurança
 const MAX_REDIRECTS: u32 = 10;
+const MAX_DELAY: u64 = 10;
+const MAX_BYTES: usize = 100_000;
+const MAX_LINES: usize = 100;
+const REDIRECT_STATUS_CODES: [u16; 5] = [301, 302, 303, 307, 308];
 
 // Métricas globais (thread-safe)
 #[derive(Clone)]
@@ -32,6 +71,7 @@ struct Metrics {
     delays_blocked: Arc<AtomicU64>,
     bytes_blocked: Arc<AtomicU64>,
     dangerous_urls_blocked: Arc<AtomicU64>,
+    dangerous_urls_by_reason: Arc<Mutex<HashMap<String, u64>>>,
     endpoint_stats: Arc<Mutex<HashMap<String, u64>>>,
 }
 
@@ -45,6 +85,7 @@ impl Metrics {
             delays_blocked: Arc::new(AtomicU64::new(0)),
             bytes_blocked: Arc::new(AtomicU64::new(0)),
             dangerous_urls_blocked: Arc::new(AtomicU64::new(0)),
+            dangerous_urls_by_reason: Arc::new(Mutex::new(HashMap::new())),
             endpoint_stats: Arc::new(Mutex::new(HashMap::new())),
         }
     }
@@ -73,8 +114,11 @@ impl Metrics {
         self.bytes_blocked.fetch_add(1, Ordering::Relaxed);
     }
 
-    fn increment_dangerous_urls(&self) {
+    fn increment_dangerous_urls(&self, reason: &str) {
         self.dangerous_urls_blocked.fetch_add(1, Ordering::Relaxed);
+        if let Ok(mut reasons) = self.dangerous_urls_by_reason.lock() {
+            *reasons.entry(reason.to_string()).or_insert(0) += 1;
+        }
     }
 
     fn record_endpoint(&self, endpoint: String) {
@@ -91,6 +135,13 @@ impl Metrics {
             .map(|stats| stats.clone())
             .unwrap_or_default();
 
+        let dangerous_urls_by_reason = self
+            .dangerous_urls_by_reason
+            .lock()
+            .ok()
+            .map(|reasons| reasons.clone())
+            .unwrap_or_default();
+        
         MetricsResponse {
             total_requests: self.total_requests.load(Ordering::Relaxed),
             successful_requests: self.successful_requests.load(Ordering::Relaxed),
@@ -100,6 +151,7 @@ impl Metrics {
                 delays_blocked: self.delays_blocked.load(Ordering::Relaxed),
                 bytes_blocked: self.bytes_blocked.load(Ordering::Relaxed),
                 dangerous_urls_blocked: self.dangerous_urls_blocked.load(Ordering::Relaxed),
+                dangerous_urls_by_reason,
             },
             endpoint_stats,
         }
@@ -121,6 +173,7 @@ struct SecurityBlocks {
     delays_blocked: u64,
     bytes_blocked: u64,
     dangerous_urls_blocked: u64,
+    dangerous_urls_by_reason: HashMap<String, u64>,
 }
 
 #[tokio::main]
@@ -135,6 +188,10 @@ async fn main() {
         .init();
 
     let app_state = Arc::new(AppState::new());
+    
+    if app_state.config.redaction.full_echo() {
+        tracing::warn!("⚠️  Eco completo habilitado: segredos não serão ocultados");
+    }
 
     // Configura as rotas
     let app = Router::new()
@@ -167,16 +224,33 @@ async fn main() {
         // Autenticação
         .route("/basic-auth/:user/:password", get(handle_basic_auth))
         .route("/bearer", get(handle_bearer_auth))
+        .route("/api-key", get(handle_api_key_auth))
+        .route("/api-key/:scope", get(handle_api_key_auth_scoped))
+        
+        // Sessões e simulador de login
+        .route("/session/login", get(session::handle_login_page))
+        .route("/session/login", post(session::handle_login))
+        .route("/session/me", get(session::handle_me))
+        .route("/session/rotate", post(session::handle_rotate))
+        .route("/session/logout", post(session::handle_logout))
         
         // Redirecionamentos
-        .route("/redirect/:n", get(handle_redirect))
-        .route("/redirect-to", get(handle_redirect_to))
-        .route("/absolute-redirect/:n", get(handle_absolute_redirect))
+        .route("/redirect/:n", any(handle_redirect))
+        .route("/redirect-to", any(handle_redirect_to))
+        .route("/relative-redirect/:n", any(handle_relative_redirect))
+        .route("/absolute-redirect/:n", any(handle_absolute_redirect))
+        .route("/redirect-loop", any(handle_redirect_loop))
         
         // Response formats
         .route("/json", get(handle_json))
         .route("/html", get(handle_html))
         .route("/xml", get(handle_xml))
+        .route("/negotiate", get(handle_negotiate))
+        .route("/encoding/utf8", get(handle_encoding_utf8))
+        
+        // Robots
+        .route("/robots.txt", get(handle_robots_txt))
+        .route("/deny", get(handle_deny))
         
         // Imagens
         .route("/image", get(handle_image))
@@ -202,26 +276,135 @@ async fn main() {
         .route("/health", get(handle_health))
         
         // Anything
-        .route("/anything", get(handle_anything))
-        .route("/anything", post(handle_anything))
-        .route("/anything/*path", get(handle_anything))
-        .route("/anything/*path", post(handle_anything))
+        .route("/anything", any(handle_anything))
+        .route("/anything/*path", any(handle_anything))
         
         // Home
         .route("/", get(handle_home))
         
+        // GraphQL (consultas, mutations, subscriptions via WebSocket e GraphiQL)
+        .merge(graphql::routes(app_state.clone()))
+        
+        // gRPC e gRPC-Web (mesma porta, HTTP/2)
+        .merge(grpc::routes(app_state.clone()))
+        
+        // TLS (parâmetros negociados e CA gerada)
+        .merge(tls::routes())
+        
+        // Versão do HTTP, stream e reaproveitamento da conexão
+        .merge(protocol::routes())
+        
+        // Falhas no nível da conexão (RST, corpo truncado, framing inválido)
+        .merge(fault::routes())
+        
+        // Mocks programáveis (/__admin/mappings)
+        .merge(mock::routes(&app_state))
+        .merge(journal::routes(&app_state))
+        
+        // Proxy de gravação e reprodução (/__admin/recordings)
+        .merge(proxy::routes(&app_state))
+        
+        // Documento OpenAPI gerado da configuração e página interativa (/docs)
+        .merge(openapi::routes())
+        
         .layer(CorsLayer::permissive())
-        .with_state(app_state);
+        .with_state(app_state.clone());
+    
+    // Gravação (repassa ao upstream) ou reprodução (responde com o que foi gravado)
+    let app = if app_state.config.proxy.mode != proxy::ProxyMode::Off {
+        app.layer(axum::middleware::from_fn_with_state(app_state.clone(), proxy::layer))
+    } else {
+        app
+    };
+    
+    // Mapeamentos registrados respondem antes das rotas fixas
+    let app = if app_state.config.mocks.enabled {
+        app.layer(axum::middleware::from_fn_with_state(app_state.clone(), mock::layer))
+    } else {
+        app
+    };
+    
+    // Caos por headers (X-RustJin-Delay, X-RustJin-Fail-Rate...) em qualquer rota
+    let app = if app_state.config.chaos.enabled {
+        app.layer(axum::middleware::from_fn_with_state(app_state.clone(), chaos::layer))
+    } else {
+        app
+    };
+    
+    // Journal por fora de tudo: vê a resposta final, inclusive as de mocks e de caos
+    let app = if app_state.config.mocks.enabled {
+        app.layer(axum::middleware::from_fn_with_state(app_state.clone(), journal::layer))
+    } else {
+        app
+    };
 
+    // Só anuncia o HTTP/3 quando o listener QUIC sobe de fato (precisa do TLS carregado)
+    let app = if app_state.tls.is_some() && app_state.config.http3.enabled {
+        app.layer(axum::middleware::from_fn_with_state(
+            app_state.config.http3.alt_svc(),
+            http3::advertise,
+        ))
+    } else {
+        app
+    };
+    
     let addr = SocketAddr::from(([0, 0, 0, 0], 8105));
     tracing::info!("🚀 Servidor RustJin iniciado");
     tracing::info!("📡 Porta: {}", addr.port());
     tracing::info!("🌐 URL: https://rustjin.blackcerb.com.br");
     tracing::info!("📊 Métricas: https://rustjin.blackcerb.com.br/metrics");
     tracing::info!("💚 Health: https://rustjin.blackcerb.com.br/health");
+    tracing::info!("🧬 GraphQL: https://rustjin.blackcerb.com.br/graphql");
+    tracing::info!("🔌 gRPC: rustjin.echo.v1.EchoService (com reflection e gRPC-Web)");
 
-    let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
-    axum::serve(listener, app).await.unwrap();
+    let tls_server = match &app_state.tls {
+        Some(setup) => {
+            let tls_addr = SocketAddr::from(([0, 0, 0, 0], app_state.config.tls.port));
+            let listener = tokio::net::TcpListener::bind(tls_addr).await.unwrap();
+            tracing::info!("🔒 HTTPS: porta {}", tls_addr.port());
+            if setup.ca_pem.is_some() {
+                tracing::info!("📜 Certificado autoassinado gerado; CA em /tls/ca.pem");
+            }
+            Some(tls::serve(listener, setup, app.clone(), app_state.clone()))
+        }
+        None => None,
+    };
+    
+    let http3_server = match (&app_state.tls, app_state.config.http3.enabled) {
+        (Some(setup), true) => {
+            let endpoint = http3::endpoint(&app_state.config.http3, setup)
+                .unwrap_or_else(|e| panic!("❌ HTTP/3: {}", e));
+            tracing::info!("⚡ HTTP/3: porta {} (UDP)", app_state.config.http3.port);
+            Some(http3::serve(endpoint, app.clone()))
+        }
+        _ => None,
+    };
+    
+    let plain_server = if app_state.config.tls.only {
+        tracing::info!("🔒 HTTP em texto puro desabilitado (RUSTJIN_TLS_ONLY)");
+        None
+    } else {
+        let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+        Some(protocol::serve(listener, app, app_state.clone()))
+    };
+    
+    tokio::join!(
+        async {
+            if let Some(server) = plain_server {
+                server.await
+            }
+        },
+        async {
+            if let Some(server) = tls_server {
+                server.await
+            }
+        },
+        async {
+            if let Some(server) = http3_server {
+                server.await
+            }
+        },
+    );
 }
 
 // State compartilhado
@@ -229,20 +412,42 @@ async fn main() {
 struct AppState {
     start_time: chrono::DateTime<chrono::Utc>,
     metrics: Metrics,
+    config: Config,
+    sessions: SessionStore,
+    tls: Option<tls::TlsSetup>,
+    mocks: mock::MockStore,
+    journal: journal::Journal,
+    proxy: proxy::Proxy,
 }
 
 impl AppState {
     fn new() -> Self {
+        let config = Config::from_env();
+        let tls = config.tls.enabled.then(|| {
+            tls::TlsSetup::load(&config.tls).unwrap_or_else(|e| panic!("❌ TLS: {}", e))
+        });
+        let mocks = mock::MockStore::load(&config.mocks).unwrap_or_else(|e| panic!("❌ Mocks: {}", e));
+        let journal = journal::Journal::new(config.mocks.journal_limit);
+        let proxy = proxy::Proxy::load(&config.proxy).unwrap_or_else(|e| panic!("❌ Proxy: {}", e));
+        
         Self {
             start_time: chrono::Utc::now(),
             metrics: Metrics::new(),
+            config,
+            sessions: SessionStore::new(),
+            tls,
+            mocks,
+            journal,
+            proxy,
         }
     }
 }
 
 // Estruturas de resposta
-#[derive(Serialize)]
+#[derive(Clone, Serialize)]
 struct RequestInfo {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    method: Option<String>,
     args: HashMap<String, String>,
     headers: HashMap<String, String>,
     origin: String,
@@ -253,18 +458,26 @@ struct RequestInfo {
     json: Option<Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     form: Option<HashMap<String, String>>,
+    // Corpo que não decodificou no formato declarado; ele continua em "data"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    decode_error: Option<String>,
 }
 
 // Função auxiliar para extrair informações da requisição
 fn extract_request_info(
+    redaction: &Redaction,
     headers: &HeaderMap,
     query: Query<HashMap<String, String>>,
-    body: Option<String>,
+    body: Option<Bytes>,
 ) -> RequestInfo {
-    let headers_map: HashMap<String, String> = headers
+    let mut headers_map: HashMap<String, String> = headers
         .iter()
         .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
         .collect();
+    redaction.headers(&mut headers_map);
+    
+    let mut args = query.0;
+    redaction.query(&mut args);
 
     let origin = headers
         .get("x-forwarded-for")
@@ -273,16 +486,118 @@ fn extract_request_info(
         .unwrap_or("127.0.0.1")
         .to_string();
 
-    let json_data = body.as_ref().and_then(|b| serde_json::from_str(b).ok());
+    let content_type = headers.get(CONTENT_TYPE).and_then(|v| v.to_str().ok());
+    let mut data = None;
+    let mut json_data: Option<Value> = None;
+    let mut form = None;
+    let mut decode_error = None;
+    
+    if let Some(body) = body {
+        use base64::{Engine as _, engine::general_purpose};
+        
+        // Corpos binários (MessagePack, CBOR) aparecem como data URL em base64
+        data = Some(match std::str::from_utf8(&body) {
+            Ok(text) => text.to_string(),
+            Err(_) => format!(
+                "data:{};base64,{}",
+                content_type.unwrap_or("application/octet-stream"),
+                general_purpose::STANDARD.encode(&body)
+            ),
+        });
+        
+        // Formato declarado no Content-Type; sem declaração, tenta JSON. Como no httpbin,
+        // um corpo inválido é ecoado mesmo assim, com o motivo em "decode_error"
+        json_data = match content_type.and_then(formats::Format::from_media_type) {
+            Some(format) if format != formats::Format::Json && !body.is_empty() => {
+                match formats::decode(format, &body) {
+                    Ok(value) => Some(value),
+                    Err(e) => {
+                        decode_error = Some(format!("Invalid {} body: {}", format.name(), e));
+                        None
+                    }
+                }
+            }
+            _ => serde_json::from_slice(&body).ok(),
+        };
+        
+        // Formulário: os campos sensíveis são ocultados em "form" e também no "data" bruto
+        if content_type.is_some_and(|t| t.starts_with("application/x-www-form-urlencoded")) {
+            if let Ok(mut pairs) = serde_urlencoded::from_bytes::<Vec<(String, String)>>(&body) {
+                if redaction.form(&mut pairs) {
+                    data = serde_urlencoded::to_string(&pairs).ok();
+                }
+                form = Some(pairs.into_iter().collect());
+            }
+        }
+    }
+    
+    // O corpo bruto repetiria os segredos ocultados no JSON
+    if let Some(value) = json_data.as_mut() {
+        if redaction.json(value) {
+            data = Some(value.to_string());
+        }
+    }
 
     RequestInfo {
-        args: query.0,
+        method: None,
+        args,
         headers: headers_map,
         origin,
         url: "https://rustjin.blackcerb.com.br".to_string(),
-        data: body.clone(),
+        data,
         json: json_data,
-        form: None,
+        form,
+        decode_error,
+    }
+}
+
+// Resposta dos endpoints de eco, no formato pedido por ?format= ou pelo header Accept
+fn echo_response(
+    state: &AppState,
+    method: Option<&Method>,
+    headers: &HeaderMap,
+    query: Query<HashMap<String, String>>,
+    body: Option<Bytes>,
+) -> axum::response::Response {
+    let accept = headers.get(ACCEPT).and_then(|v| v.to_str().ok());
+    let format = match formats::Format::select(query.get("format").map(String::as_str), accept) {
+        Ok(format) => format,
+        Err(message) => {
+            state.metrics.increment_failed();
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": "Unsupported format",
+                    "supported": formats::NAMES,
+                    "message": message
+                }))
+            ).into_response();
+        }
+    };
+    
+    let mut info = extract_request_info(&state.config.redaction, headers, query, body);
+    info.method = method.map(Method::to_string);
+    
+    match formats::encode(format, &info) {
+        Ok(bytes) => {
+            state.metrics.increment_success();
+            (
+                StatusCode::OK,
+                [(CONTENT_TYPE, format.content_type())],
+                bytes
+            ).into_response()
+        }
+        Err(e) => {
+            state.metrics.increment_failed();
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": "Serialization failed",
+                    "format": format.name(),
+                    "message": e
+                }))
+            ).into_response()
+        }
     }
 }
 
@@ -358,48 +673,44 @@ async fn handle_get(
 ) -> impl IntoResponse {
     state.metrics.increment_total();
     state.metrics.record_endpoint("/get".to_string());
-    state.metrics.increment_success();
     
-    Json(extract_request_info(&headers, query, None))
+    echo_response(&state, None, &headers, query, None)
 }
 
 async fn handle_post(
     axum::extract::State(state): axum::extract::State<Arc<AppState>>,
     headers: HeaderMap,
     query: Query<HashMap<String, String>>,
-    body: String,
+    body: Bytes,
 ) -> impl IntoResponse {
     state.metrics.increment_total();
     state.metrics.record_endpoint("/post".to_string());
-    state.metrics.increment_success();
     
-    Json(extract_request_info(&headers, query, Some(body)))
+    echo_response(&state, None, &headers, query, Some(body))
 }
 
 async fn handle_put(
     axum::extract::State(state): axum::extract::State<Arc<AppState>>,
     headers: HeaderMap,
     query: Query<HashMap<String, String>>,
-    body: String,
+    body: Bytes,
 ) -> impl IntoResponse {
     state.metrics.increment_total();
     state.metrics.record_endpoint("/put".to_string());
-    state.metrics.increment_success();
     
-    Json(extract_request_info(&headers, query, Some(body)))
+    echo_response(&state, None, &headers, query, Some(body))
 }
 
 async fn handle_patch(
     axum::extract::State(state): axum::extract::State<Arc<AppState>>,
     headers: HeaderMap,
     query: Query<HashMap<String, String>>,
-    body: String,
+    body: Bytes,
 ) -> impl IntoResponse {
     state.metrics.increment_total();
     state.metrics.record_endpoint("/patch".to_string());
-    state.metrics.increment_success();
     
-    Json(extract_request_info(&headers, query, Some(body)))
+    echo_response(&state, None, &headers, query, Some(body))
 }
 
 async fn handle_delete(
@@ -409,9 +720,8 @@ async fn handle_delete(
 ) -> impl IntoResponse {
     state.metrics.increment_total();
     state.metrics.record_endpoint("/delete".to_string());
-    state.metrics.increment_success();
     
-    Json(extract_request_info(&headers, query, None))
+    echo_response(&state, None, &headers, query, None)
 }
 
 async fn handle_headers(
@@ -421,10 +731,11 @@ async fn handle_headers(
     state.metrics.increment_total();
     state.metrics.record_endpoint("/headers".to_string());
     
-    let headers_map: HashMap<String, String> = headers
+    let mut headers_map: HashMap<String, String> = headers
         .iter()
         .map(|(k, v)| (k.to_string(), v.to_str().unwrap_or("").to_string()))
         .collect();
+    state.config.redaction.headers(&mut headers_map);
     
     state.metrics.increment_success();
     Json(json!({ "headers": headers_map }))
@@ -488,8 +799,6 @@ async fn handle_delay(
     state.metrics.increment_total();
     state.metrics.record_endpoint(format!("/delay/{}", seconds));
     
-    const MAX_DELAY: u64 = 10;
-    
     if seconds > MAX_DELAY {
         state.metrics.increment_delays_blocked();
         state.metrics.increment_failed();
@@ -525,71 +834,120 @@ async fn handle_cookies_get(
     state.metrics.increment_total();
     state.metrics.record_endpoint("/cookies".to_string());
     
-    let cookies = headers
-        .get("cookie")
-        .and_then(|v| v.to_str().ok())
-        .unwrap_or("");
-    
-    let cookies_map: HashMap<String, String> = cookies
-        .split(';')
-        .filter_map(|c| {
-            let parts: Vec<&str> = c.trim().splitn(2, '=').collect();
-            if parts.len() == 2 {
-                Some((parts[0].to_string(), parts[1].to_string()))
-            } else {
-                None
-            }
-        })
-        .collect();
+    let mut cookies_map = cookies::parse_cookie_header(&headers);
+    state.config.redaction.cookies(&mut cookies_map);
     
     state.metrics.increment_success();
     Json(json!({ "cookies": cookies_map }))
 }
 
-#[derive(Deserialize)]
-struct CookieParams {
-    #[serde(flatten)]
-    cookies: HashMap<String, String>,
+fn cookie_error(state: &AppState, message: String) -> axum::response::Response {
+    state.metrics.increment_failed();
+    tracing::warn!("🍪 Cookie inválido: {}", message);
+    
+    (
+        StatusCode::BAD_REQUEST,
+        Json(json!({
+            "error": "Invalid cookie",
+            "message": message
+        }))
+    ).into_response()
+}
+
+// Envia os Set-Cookie e, se pedido, redireciona para /cookies como o httpbin
+fn cookie_response(
+    state: &AppState,
+    cookies: Vec<cookie::Cookie<'static>>,
+    redirect: bool,
+    body: Value,
+) -> axum::response::Response {
+    let mut headers = HeaderMap::new();
+    
+    for cookie in cookies {
+        match HeaderValue::from_str(&cookie.to_string()) {
+            Ok(value) => {
+                headers.append(SET_COOKIE, value);
+            }
+            Err(_) => return cookie_error(state, format!("Cannot encode cookie '{}'", cookie.name())),
+        }
+    }
+    
+    state.metrics.increment_success();
+    
+    if redirect {
+        headers.insert(LOCATION, HeaderValue::from_static("/cookies"));
+        return (StatusCode::FOUND, headers).into_response();
+    }
+    
+    (headers, Json(body)).into_response()
 }
 
 async fn handle_cookies_set(
     axum::extract::State(state): axum::extract::State<Arc<AppState>>,
-    Query(params): Query<CookieParams>,
+    Query(params): Query<Vec<(String, String)>>,
 ) -> impl IntoResponse {
     state.metrics.increment_total();
     state.metrics.record_endpoint("/cookies/set".to_string());
     
-    let cookie_headers: Vec<_> = params
-        .cookies
-        .iter()
-        .map(|(k, v)| format!("{}={}", k, v))
-        .collect();
+    let attributes = match CookieAttributes::from_params(&params) {
+        Ok(attributes) => attributes,
+        Err(message) => return cookie_error(&state, message),
+    };
     
-    let mut headers = HeaderMap::new();
-    for cookie in cookie_headers {
-        headers.append("set-cookie", cookie.parse().unwrap());
+    let mut set = Vec::new();
+    let mut cookies_map = HashMap::new();
+    
+    for (name, value) in params.iter().filter(|(name, _)| !cookies::is_attribute_param(name)) {
+        match attributes.set_cookie(name, value) {
+            Ok(cookie) => set.push(cookie),
+            Err(message) => return cookie_error(&state, message),
+        }
+        cookies_map.insert(name.clone(), value.clone());
     }
     
-    state.metrics.increment_success();
-    (headers, Json(json!({ "cookies": params.cookies })))
+    let set_cookie: Vec<String> = set.iter().map(|c| c.to_string()).collect();
+    
+    cookie_response(
+        &state,
+        set,
+        attributes.redirect,
+        json!({ "cookies": cookies_map, "set_cookie": set_cookie }),
+    )
 }
 
 async fn handle_cookies_delete(
     axum::extract::State(state): axum::extract::State<Arc<AppState>>,
-    Query(params): Query<HashMap<String, String>>,
+    Query(params): Query<Vec<(String, String)>>,
 ) -> impl IntoResponse {
     state.metrics.increment_total();
     state.metrics.record_endpoint("/cookies/delete".to_string());
     
-    let mut headers = HeaderMap::new();
+    let attributes = match CookieAttributes::from_params(&params) {
+        Ok(attributes) => attributes,
+        Err(message) => return cookie_error(&state, message),
+    };
     
-    if let Some(name) = params.get("name") {
-        let cookie = format!("{}=; Max-Age=0", name);
-        headers.append("set-cookie", cookie.parse().unwrap());
+    // Aceita ?name=a&name=b e também o estilo do httpbin, ?a&b
+    let names: Vec<&String> = params
+        .iter()
+        .filter(|(name, _)| !cookies::is_attribute_param(name))
+        .map(|(name, value)| if name == "name" { value } else { name })
+        .collect();
+    
+    let mut removals = Vec::new();
+    for name in &names {
+        match attributes.removal_cookie(name) {
+            Ok(cookie) => removals.push(cookie),
+            Err(message) => return cookie_error(&state, message),
+        }
     }
     
-    state.metrics.increment_success();
-    (headers, Json(json!({ "message": "Cookie deleted" })))
+    cookie_response(
+        &state,
+        removals,
+        attributes.redirect,
+        json!({ "message": "Cookie deleted", "deleted": names }),
+    )
 }
 
 async fn handle_basic_auth(
@@ -604,8 +962,8 @@ async fn handle_basic_auth(
     
     if let Some(auth_header) = headers.get("authorization") {
         if let Ok(auth_str) = auth_header.to_str() {
-            if auth_str.starts_with("Basic ") {
-                if let Ok(decoded) = general_purpose::STANDARD.decode(&auth_str[6..]) {
+            if let Some(encoded) = auth_str.strip_prefix("Basic ") {
+                if let Ok(decoded) = general_purpose::STANDARD.decode(encoded) {
                     if let Ok(credentials) = String::from_utf8(decoded) {
                         let parts: Vec<&str> = credentials.splitn(2, ':').collect();
                         if parts.len() == 2 && parts[0] == user && parts[1] == password {
@@ -636,13 +994,12 @@ async fn handle_bearer_auth(
     
     if let Some(auth_header) = headers.get("authorization") {
         if let Ok(auth_str) = auth_header.to_str() {
-            if auth_str.starts_with("Bearer ") {
-                let token = &auth_str[7..];
+            if let Some(token) = auth_str.strip_prefix("Bearer ") {
                 state.metrics.increment_success();
                 tracing::info!("✅ Autenticação bearer bem-sucedida");
                 return Ok(Json(json!({
                     "authenticated": true,
-                    "token": token
+                    "token": state.config.redaction.secret(token)
                 })));
             }
         }
@@ -653,18 +1010,209 @@ async fn handle_bearer_auth(
     Err(StatusCode::UNAUTHORIZED)
 }
 
-async fn handle_redirect(
+// Procura a chave de API no header, na query string e no cookie, nessa ordem
+fn find_api_key(
+    state: &AppState,
+    headers: &HeaderMap,
+    query: &HashMap<String, String>,
+) -> Option<(String, &'static str)> {
+    let config = &state.config.api_keys;
+    
+    if let Some(key) = headers
+        .get(config.header_name.as_str())
+        .and_then(|v| v.to_str().ok())
+    {
+        return Some((key.to_string(), "header"));
+    }
+    
+    if let Some(key) = query.get(&config.query_param) {
+        return Some((key.clone(), "query"));
+    }
+    
+    cookies::parse_cookie_header(headers)
+        .remove(&config.cookie_name)
+        .map(|key| (key, "cookie"))
+}
+
+fn check_api_key(
+    state: &AppState,
+    headers: &HeaderMap,
+    query: &HashMap<String, String>,
+    required_scope: Option<&str>,
+) -> axum::response::Response {
+    let config = &state.config.api_keys;
+    let challenge = format!(
+        "ApiKey header=\"{}\", query=\"{}\", cookie=\"{}\"",
+        config.header_name, config.query_param, config.cookie_name
+    );
+    
+    let Some((key, source)) = find_api_key(state, headers, query) else {
+        state.metrics.increment_failed();
+        tracing::warn!("❌ Chave de API ausente");
+        
+        return (
+            StatusCode::UNAUTHORIZED,
+            [("www-authenticate", challenge)],
+            Json(json!({
+                "error": "Missing API key",
+                "header": config.header_name,
+                "query_param": config.query_param,
+                "cookie": config.cookie_name,
+                "message": "Provide an API key via header, query parameter or cookie"
+            }))
+        ).into_response();
+    };
+    
+    let Some(scopes) = config.keys.get(key.trim()) else {
+        state.metrics.increment_failed();
+        tracing::warn!("❌ Chave de API inválida (origem: {})", source);
+        
+        return (
+            StatusCode::UNAUTHORIZED,
+            [("www-authenticate", challenge)],
+            Json(json!({
+                "error": "Invalid API key",
+                "source": source,
+                "message": "The API key provided is not recognized"
+            }))
+        ).into_response();
+    };
+    
+    if let Some(scope) = required_scope {
+        if !scopes.iter().any(|s| s == scope) {
+            state.metrics.increment_failed();
+            tracing::warn!("🚫 Chave de API sem o escopo necessário: {}", scope);
+            
+            return (
+                StatusCode::FORBIDDEN,
+                Json(json!({
+                    "error": "Insufficient scope",
+                    "required_scope": scope,
+                    "granted_scopes": scopes,
+                    "message": format!("API key does not grant the '{}' scope", scope)
+                }))
+            ).into_response();
+        }
+    }
+    
+    state.metrics.increment_success();
+    tracing::info!("✅ Autenticação por chave de API bem-sucedida (origem: {})", source);
+    
+    Json(json!({
+        "authenticated": true,
+        "source": source,
+        "scopes": scopes,
+        "required_scope": required_scope
+    })).into_response()
+}
+
+async fn handle_api_key_auth(
     axum::extract::State(state): axum::extract::State<Arc<AppState>>,
-    Path(n): Path<u32>,
+    headers: HeaderMap,
+    Query(query): Query<HashMap<String, String>>,
 ) -> impl IntoResponse {
     state.metrics.increment_total();
-    state.metrics.record_endpoint(format!("/redirect/{}", n));
+    state.metrics.record_endpoint("/api-key".to_string());
+    
+    check_api_key(&state, &headers, &query, None)
+}
+
+async fn handle_api_key_auth_scoped(
+    axum::extract::State(state): axum::extract::State<Arc<AppState>>,
+    Path(scope): Path<String>,
+    headers: HeaderMap,
+    Query(query): Query<HashMap<String, String>>,
+) -> impl IntoResponse {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/api-key/:scope".to_string());
+    
+    check_api_key(&state, &headers, &query, Some(&scope))
+}
+
+#[derive(Deserialize)]
+struct RedirectParams {
+    status_code: Option<u16>,
+    #[serde(default)]
+    absolute: bool,
+    // Saltos já percorridos na cadeia, propagado de um salto para o outro
+    #[serde(default)]
+    hops: u32,
+}
+
+// Tipos de cadeia de redirecionamento e o endpoint de cada uma
+#[derive(Clone, Copy, PartialEq)]
+enum RedirectChain {
+    Redirect,
+    Relative,
+    Absolute,
+}
+
+impl RedirectChain {
+    fn endpoint(self) -> &'static str {
+        match self {
+            RedirectChain::Redirect => "/redirect",
+            RedirectChain::Relative => "/relative-redirect",
+            RedirectChain::Absolute => "/absolute-redirect",
+        }
+    }
+}
+
+// Valida o status_code pedido; 302 quando não informado
+fn redirect_status(
+    state: &AppState,
+    code: Option<u16>,
+) -> Result<StatusCode, (StatusCode, Json<Value>)> {
+    let code = code.unwrap_or(302);
+    
+    if REDIRECT_STATUS_CODES.contains(&code) {
+        if let Ok(status) = StatusCode::from_u16(code) {
+            return Ok(status);
+        }
+    }
+    
+    state.metrics.increment_failed();
+    tracing::warn!("⚠️  Status de redirecionamento inválido: {}", code);
+    
+    Err((
+        StatusCode::BAD_REQUEST,
+        Json(json!({
+            "error": "Invalid redirect status",
+            "allowed": REDIRECT_STATUS_CODES,
+            "requested": code,
+            "message": "Redirect status must be one of 301, 302, 303, 307 or 308"
+        }))
+    ))
+}
+
+// 307 e 308 preservam método e corpo, então o destino final precisa ecoar ambos
+fn redirect_destination(status: StatusCode) -> &'static str {
+    match status {
+        StatusCode::TEMPORARY_REDIRECT | StatusCode::PERMANENT_REDIRECT => "/anything",
+        _ => "/get",
+    }
+}
+
+fn redirect_chain(
+    state: &AppState,
+    chain: RedirectChain,
+    n: u32,
+    params: RedirectParams,
+    headers: &HeaderMap,
+    secure: bool,
+) -> axum::response::Response {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint(format!("{}/{}", chain.endpoint(), n));
     
     if n > MAX_REDIRECTS {
         state.metrics.increment_redirects_blocked();
         state.metrics.increment_failed();
         
-        tracing::warn!("🚫 Redirecionamento bloqueado: {} (max: {})", n, MAX_REDIRECTS);
+        tracing::warn!(
+            "🚫 Redirecionamento bloqueado: {}{} (max: {})",
+            chain.endpoint(),
+            n,
+            MAX_REDIRECTS
+        );
         
         return (
             StatusCode::BAD_REQUEST,
@@ -677,27 +1225,127 @@ async fn handle_redirect(
         ).into_response();
     }
     
-    if n <= 1 {
+    // Zero saltos não é uma cadeia: o último salto sempre redireciona para o eco
+    if n == 0 {
+        state.metrics.increment_failed();
+        
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!({
+                "error": "Invalid redirect count",
+                "requested": n,
+                "message": "Redirect count must be at least 1"
+            }))
+        ).into_response();
+    }
+    
+    let status = match redirect_status(state, params.status_code) {
+        Ok(status) => status,
+        Err(rejection) => return rejection.into_response(),
+    };
+    
+    let absolute = chain == RedirectChain::Absolute || params.absolute;
+    let base = if absolute {
+        state.config.redirect.base_url(headers, secure)
+    } else {
+        String::new()
+    };
+    let hops = params.hops.saturating_add(1).to_string();
+    
+    // Último salto: redireciona para o eco, com o total de saltos no header
+    if n == 1 {
         state.metrics.increment_success();
         return (
-            StatusCode::OK,
-            [("location", "/get".to_string())],
+            status,
+            [
+                ("location", format!("{}{}", base, redirect_destination(status))),
+                ("x-redirect-hops", hops),
+            ],
             ""
         ).into_response();
     }
     
+    let query = match params.status_code {
+        Some(code) => format!("?status_code={}&hops={}", code, hops),
+        None => format!("?hops={}", hops),
+    };
+    
+    // /redirect/:n?absolute=true continua a cadeia pelos redirecionamentos absolutos
+    let next = if absolute {
+        RedirectChain::Absolute
+    } else {
+        chain
+    };
+    
+    state.metrics.increment_success();
+    let location = format!("{}{}/{}{}", base, next.endpoint(), n - 1, query);
+    (
+        status,
+        [("location", location), ("x-redirect-hops", hops)],
+        ""
+    ).into_response()
+}
+
+async fn handle_redirect(
+    axum::extract::State(state): axum::extract::State<Arc<AppState>>,
+    Path(n): Path<u32>,
+    Query(params): Query<RedirectParams>,
+    headers: HeaderMap,
+    tls: Option<Extension<TlsInfo>>,
+) -> impl IntoResponse {
+    redirect_chain(&state, RedirectChain::Redirect, n, params, &headers, tls.is_some())
+}
+
+async fn handle_relative_redirect(
+    axum::extract::State(state): axum::extract::State<Arc<AppState>>,
+    Path(n): Path<u32>,
+    Query(params): Query<RedirectParams>,
+    headers: HeaderMap,
+    tls: Option<Extension<TlsInfo>>,
+) -> impl IntoResponse {
+    redirect_chain(&state, RedirectChain::Relative, n, params, &headers, tls.is_some())
+}
+
+async fn handle_absolute_redirect(
+    axum::extract::State(state): axum::extract::State<Arc<AppState>>,
+    Path(n): Path<u32>,
+    Query(params): Query<RedirectParams>,
+    headers: HeaderMap,
+    tls: Option<Extension<TlsInfo>>,
+) -> impl IntoResponse {
+    redirect_chain(&state, RedirectChain::Absolute, n, params, &headers, tls.is_some())
+}
+
+async fn handle_redirect_loop(
+    axum::extract::State(state): axum::extract::State<Arc<AppState>>,
+    Query(params): Query<RedirectParams>,
+) -> impl IntoResponse {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/redirect-loop".to_string());
+    
+    let status = match redirect_status(&state, params.status_code) {
+        Ok(status) => status,
+        Err(rejection) => return rejection.into_response(),
+    };
+    
+    // Aponta para si mesmo: o cliente deve detectar o loop e desistir
+    let location = match params.status_code {
+        Some(code) => format!("/redirect-loop?status_code={}", code),
+        None => "/redirect-loop".to_string(),
+    };
+    
     state.metrics.increment_success();
-    let location = format!("/redirect/{}", n - 1);
     (
-        StatusCode::FOUND,
+        status,
         [("location", location)],
-        "",
+        ""
     ).into_response()
 }
 
 #[derive(Deserialize)]
 struct RedirectToParams {
     url: String,
+    status_code: Option<u16>,
 }
 
 async fn handle_redirect_to(
@@ -707,101 +1355,46 @@ async fn handle_redirect_to(
     state.metrics.increment_total();
     state.metrics.record_endpoint("/redirect-to".to_string());
     
-    let url = params.url.trim();
-    
-    let dangerous_protocols = ["javascript:", "data:", "file:", "vbscript:"];
-    let url_lower = url.to_lowercase();
-    
-    for protocol in &dangerous_protocols {
-        if url_lower.starts_with(protocol) {
-            state.metrics.increment_dangerous_urls();
+    let final_url = match state.config.redirect.check(params.url.trim()) {
+        Ok(url) => url,
+        Err(block) => {
+            state.metrics.increment_dangerous_urls(block.reason);
             state.metrics.increment_failed();
             
-            tracing::warn!("🚨 URL perigosa bloqueada: {} (protocolo: {})", url, protocol);
+            tracing::warn!(
+                "🚨 URL bloqueada: {} (motivo: {})",
+                state.config.redaction.url(&params.url),
+                block.reason
+            );
             
-            return (
-                StatusCode::BAD_REQUEST,
-                Json(json!({
-                    "error": "Invalid protocol",
-                    "message": "Protocol not allowed for security reasons"
-                }))
-            ).into_response();
+            let mut body = json!({
+                "error": block.error(),
+                "reason": block.reason,
+                "message": block.message
+            });
+            if block.reason == "too_long" {
+                body["max_length"] = json!(url_policy::MAX_URL_LENGTH);
+            }
+            
+            return (StatusCode::BAD_REQUEST, Json(body)).into_response();
         }
-    }
-    
-    if url.len() > 2048 {
-        state.metrics.increment_failed();
-        
-        tracing::warn!("⚠️  URL muito longa bloqueada: {} caracteres", url.len());
-        
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(json!({
-                "error": "URL too long",
-                "max_length": 2048,
-                "message": "URL exceeds maximum allowed length"
-            }))
-        ).into_response();
-    }
+    };
     
-    let final_url = if !url.starts_with("http://") && !url.starts_with("https://") {
-        format!("http://{}", url)
-    } else {
-        url.to_string()
+    let status = match redirect_status(&state, params.status_code) {
+        Ok(status) => status,
+        Err(rejection) => return rejection.into_response(),
     };
     
     state.metrics.increment_success();
-    tracing::info!("↪️  Redirecionando para: {}", final_url);
+    tracing::info!("↪️  Redirecionando para: {}", state.config.redaction.url(&final_url));
     
     (
-        StatusCode::FOUND,
+        status,
         [("location", final_url)],
         ""
     ).into_response()
 }
 
-async fn handle_absolute_redirect(
-    axum::extract::State(state): axum::extract::State<Arc<AppState>>,
-    Path(n): Path<u32>,
-) -> impl IntoResponse {
-    state.metrics.increment_total();
-    state.metrics.record_endpoint(format!("/absolute-redirect/{}", n));
-    
-    if n > MAX_REDIRECTS {
-        state.metrics.increment_redirects_blocked();
-        state.metrics.increment_failed();
-        
-        tracing::warn!("🚫 Redirecionamento absoluto bloqueado: {} (max: {})", n, MAX_REDIRECTS);
-        
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(json!({
-                "error": "Too many redirects",
-                "max_allowed": MAX_REDIRECTS,
-                "requested": n,
-                "message": format!("Maximum {} redirects allowed", MAX_REDIRECTS)
-            }))
-        ).into_response();
-    }
-    
-    if n <= 1 {
-        state.metrics.increment_success();
-        return (
-            StatusCode::OK,
-            [("location", "https://rustjin.blackcerb.com.br/get".to_string())],
-            "",
-        ).into_response();
-    }
-    
-    state.metrics.increment_success();
-    let location = format!("https://rustjin.blackcerb.com.br/absolute-redirect/{}", n - 1);
-    (
-        StatusCode::FOUND,
-        [("location", location)],
-        ""
-    ).into_response()
-}
-
 async fn handle_json(
     axum::extract::State(state): axum::extract::State<Arc<AppState>>,
 ) -> impl IntoResponse {
@@ -872,26 +1465,230 @@ async fn handle_xml(
     (StatusCode::OK, [("content-type", "application/xml")], xml)
 }
 
+async fn handle_negotiate(
+    axum::extract::State(state): axum::extract::State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/negotiate".to_string());
+    
+    let header = |name| headers.get(name).and_then(|v: &HeaderValue| v.to_str().ok());
+    
+    match negotiate::Variant::select(header(ACCEPT), header(ACCEPT_LANGUAGE), header(ACCEPT_CHARSET)) {
+        Some(variant) => {
+            state.metrics.increment_success();
+            (
+                StatusCode::OK,
+                [
+                    (CONTENT_TYPE, variant.content_type()),
+                    (CONTENT_LANGUAGE, variant.language.to_string()),
+                    (VARY, negotiate::VARY.to_string()),
+                ],
+                variant.render()
+            ).into_response()
+        }
+        None => {
+            state.metrics.increment_failed();
+            (
+                StatusCode::NOT_ACCEPTABLE,
+                [(VARY, negotiate::VARY)],
+                Json(json!({
+                    "error": "Not Acceptable",
+                    "available": {
+                        "media_types": negotiate::MEDIA_TYPES,
+                        "languages": negotiate::LANGUAGES,
+                        "charsets": negotiate::CHARSETS
+                    },
+                    "message": "No available representation matches the Accept, Accept-Language and Accept-Charset headers"
+                }))
+            ).into_response()
+        }
+    }
+}
+
+async fn handle_encoding_utf8(
+    axum::extract::State(state): axum::extract::State<Arc<AppState>>,
+) -> impl IntoResponse {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/encoding/utf8".to_string());
+    state.metrics.increment_success();
+    
+    let html = r#"<!DOCTYPE html>
+<html>
+<head>
+    <meta charset="utf-8">
+    <title>Unicode Demo</title>
+</head>
+<body>
+    <h1>UTF-8 encoded sample plain-text file</h1>
+    <pre>
+Português:   Ação, coração, pão, você, avô
+Deutsch:     Größe, Übermäßig, Straße
+Français:    Ça va, élève, garçon, œuvre
+Español:     ¿Qué tal? ¡Mañana! Niño
+Ελληνικά:    Καλημέρα κόσμε
+Русский:     Здравствуй, мир
+עברית:       שלום עולם
+العربية:     مرحبا بالعالم
+हिन्दी:        नमस्ते दुनिया
+日本語:      こんにちは世界
+中文:        你好，世界
+한국어:      안녕하세요 세계
+
+Matemática:  ∀x ∈ ℝ: ⌈x⌉ = −⌊−x⌋, α ∧ ¬β → γ, ∑ ∫ √ ∞ ≈ ≠ ≤ ≥
+Símbolos:    € £ ¥ © ® ™ § ¶ † ‡ • … ‰ ← ↑ → ↓ ↔
+Caixas:      ┌─┬─┐ │ │ │ ├─┼─┤ └─┴─┘ ╔═╗ ║ ║ ╚═╝
+Emoji:       🦀 🚀 🌐 ✅ 🔒 📊
+    </pre>
+</body>
+</html>"#;
+    
+    (StatusCode::OK, [("content-type", "text/html; charset=utf-8")], html)
+}
+
+async fn handle_robots_txt(
+    axum::extract::State(state): axum::extract::State<Arc<AppState>>,
+) -> impl IntoResponse {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/robots.txt".to_string());
+    state.metrics.increment_success();
+    
+    (
+        StatusCode::OK,
+        [("content-type", "text/plain")],
+        "User-agent: *\nDisallow: /deny\n"
+    )
+}
+
+async fn handle_deny(
+    axum::extract::State(state): axum::extract::State<Arc<AppState>>,
+) -> impl IntoResponse {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/deny".to_string());
+    state.metrics.increment_success();
+    
+    let angry = r#"
+          .-''''''-.
+        .' _      _ '.
+       /   O      O   \
+      :                :
+      |                |
+      :       __       :
+       \  .-"`  `"-.  /
+        '.          .'
+          '-......-'
+     YOU SHOULDN'T BE HERE
+"#;
+    
+    (StatusCode::OK, [("content-type", "text/plain")], angry)
+}
+
+#[derive(Deserialize)]
+struct ImageParams {
+    width: Option<u32>,
+    height: Option<u32>,
+    color: Option<String>,
+    text: Option<String>,
+}
+
+fn render_image(
+    state: &AppState,
+    format: images::ImageFormat,
+    params: &ImageParams,
+) -> axum::response::Response {
+    let spec = match images::ImageSpec::new(
+        params.width,
+        params.height,
+        params.color.as_deref(),
+        params.text.as_deref(),
+    ) {
+        Ok(spec) => spec,
+        Err(message) => {
+            state.metrics.increment_failed();
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": "Invalid image parameters",
+                    "max_dimension": images::MAX_DIMENSION,
+                    "message": message
+                }))
+            ).into_response();
+        }
+    };
+    
+    match images::render(format, &spec) {
+        Ok(bytes) => {
+            state.metrics.increment_success();
+            (
+                StatusCode::OK,
+                [(CONTENT_TYPE, format.content_type())],
+                bytes
+            ).into_response()
+        }
+        Err(e) => {
+            state.metrics.increment_failed();
+            tracing::error!("🖼️  Falha ao gerar imagem {}: {}", format.content_type(), e);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!({
+                    "error": "Image encoding failed",
+                    "message": e
+                }))
+            ).into_response()
+        }
+    }
+}
+
 async fn handle_image(
     axum::extract::State(state): axum::extract::State<Arc<AppState>>,
+    headers: HeaderMap,
+    Query(params): Query<ImageParams>,
 ) -> impl IntoResponse {
     state.metrics.increment_total();
     state.metrics.record_endpoint("/image".to_string());
-    state.metrics.increment_success();
     
-    let svg = r##"<svg width="200" height="200" xmlns="http://www.w3.org/2000/svg">
-        <rect width="200" height="200" fill="#3498db"/>
-        <text x="50%" y="50%" text-anchor="middle" fill="white" font-size="20">HTTPBin</text>
-    </svg>"##;
+    let accept = headers.get(ACCEPT).and_then(|v| v.to_str().ok());
     
-    (StatusCode::OK, [("content-type", "image/svg+xml")], svg)
+    match negotiate::best_media_type(accept, &images::MEDIA_TYPES)
+        .and_then(images::ImageFormat::from_media_type)
+    {
+        Some(format) => render_image(&state, format, &params),
+        None => {
+            state.metrics.increment_failed();
+            (
+                StatusCode::NOT_ACCEPTABLE,
+                Json(json!({
+                    "error": "Not Acceptable",
+                    "accept": images::MEDIA_TYPES,
+                    "message": "Client did not request a supported media type"
+                }))
+            ).into_response()
+        }
+    }
 }
 
 async fn handle_image_format(
     axum::extract::State(state): axum::extract::State<Arc<AppState>>,
-    Path(_format): Path<String>,
+    Path(format): Path<String>,
+    Query(params): Query<ImageParams>,
 ) -> impl IntoResponse {
-    handle_image(axum::extract::State(state)).await
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/image/:format".to_string());
+    
+    match images::ImageFormat::from_name(&format) {
+        Some(format) => render_image(&state, format, &params),
+        None => {
+            state.metrics.increment_failed();
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({
+                    "error": "Unsupported image format",
+                    "supported": ["png", "jpeg", "webp", "gif", "svg"],
+                    "requested": format
+                }))
+            ).into_response()
+        }
+    }
 }
 
 async fn handle_bytes(
@@ -901,8 +1698,6 @@ async fn handle_bytes(
     state.metrics.increment_total();
     state.metrics.record_endpoint(format!("/bytes/{}", n));
     
-    const MAX_BYTES: usize = 100_000;
-    
     if n > MAX_BYTES {
         state.metrics.increment_bytes_blocked();
         state.metrics.increment_failed();
@@ -937,8 +1732,6 @@ async fn handle_stream(
     state.metrics.increment_total();
     state.metrics.record_endpoint(format!("/stream/{}", n));
     
-    const MAX_LINES: usize = 100;
-    
     if n > MAX_LINES {
         state.metrics.increment_failed();
         
@@ -1015,13 +1808,13 @@ async fn handle_base64_decode(
 
 async fn handle_anything(
     axum::extract::State(state): axum::extract::State<Arc<AppState>>,
+    method: Method,
     headers: HeaderMap,
     query: Query<HashMap<String, String>>,
-    body: Option<String>,
+    body: Bytes,
 ) -> impl IntoResponse {
     state.metrics.increment_total();
     state.metrics.record_endpoint("/anything".to_string());
-    state.metrics.increment_success();
     
-    Json(extract_request_info(&headers, query, body))
+    echo_response(&state, Some(&method), &headers, query, Some(body))
 }
\ No newline at end of file
@@ -0,0 +1,1018 @@
+use axum::{
+    body::{Body, Bytes},
+    extract::{Path, Query, Request, State},
+    http::{header::CONTENT_TYPE, HeaderMap, HeaderName, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+    routing::{get, post, put},
+    Router,
+};
+use base64::{engine::general_purpose, Engine as _};
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{json, Value};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use crate::config::{env_flag, env_or};
+use crate::har;
+use crate::template::{self, Context, Counters};
+use crate::{extract_request_info, AppState, MAX_DELAY};
+
+// Rotas administrativas, nunca interceptadas pelos mocks
+pub const ADMIN_PREFIX: &str = "/__admin";
+
+// Corpo lido para avaliar os predicados de um mapeamento (e guardado no journal)
+pub const MAX_BODY: usize = 2 * 1024 * 1024;
+
+const DEFAULT_PRIORITY: u32 = 5;
+
+// Estado inicial de todo cenário, e o estado para onde volta em um reset
+pub const STARTED: &str = "Started";
+
+// Mocks programáveis em tempo de execução; desligados por padrão
+#[derive(Clone)]
+pub struct MockConfig {
+    pub enabled: bool,
+    dir: Option<PathBuf>,
+    // HAR importado na inicialização, respondendo por método e caminho
+    har: Option<PathBuf>,
+    // Requisições guardadas para /__admin/requests
+    pub journal_limit: usize,
+}
+
+impl MockConfig {
+    pub fn from_env() -> Self {
+        let path = |name| {
+            std::env::var(name)
+                .ok()
+                .filter(|v: &String| !v.trim().is_empty())
+                .map(PathBuf::from)
+        };
+        let dir = path("RUSTJIN_MOCKS_DIR");
+        let har = path("RUSTJIN_HAR_FILE");
+        
+        Self {
+            enabled: env_flag("RUSTJIN_MOCKS") || dir.is_some() || har.is_some(),
+            dir,
+            har,
+            journal_limit: env_or("RUSTJIN_JOURNAL_LIMIT", "1000").parse().unwrap_or(1000),
+        }
+    }
+}
+
+// Expressão regular validada no cadastro e serializada como texto
+#[derive(Clone)]
+struct Pattern(Regex);
+
+impl Serialize for Pattern {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for Pattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        Regex::new(&pattern).map(Pattern).map_err(serde::de::Error::custom)
+    }
+}
+
+// Predicado sobre um texto (query, header); um texto simples equivale a equal_to
+#[derive(Clone, Serialize)]
+#[serde(untagged)]
+enum TextMatcher {
+    Exact(String),
+    Rules(TextRules),
+}
+
+// Sem #[serde(untagged)] na leitura, para que o erro aponte a regra inválida
+impl<'de> Deserialize<'de> for TextMatcher {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Value::deserialize(deserializer)? {
+            Value::String(text) => Ok(Self::Exact(text)),
+            value => serde_json::from_value(value)
+                .map(Self::Rules)
+                .map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TextRules {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    equal_to: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    contains: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    matches: Option<Pattern>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    absent: bool,
+}
+
+impl TextRules {
+    fn matches(&self, value: &str) -> bool {
+        self.equal_to.as_ref().is_none_or(|expected| value == expected)
+            && self.contains.as_ref().is_none_or(|part| value.contains(part.as_str()))
+            && self.matches.as_ref().is_none_or(|pattern| pattern.0.is_match(value))
+    }
+}
+
+impl TextMatcher {
+    fn matches(&self, value: Option<&str>) -> bool {
+        match (self, value) {
+            (Self::Exact(expected), Some(value)) => value == expected,
+            (Self::Rules(rules), value) if rules.absent => value.is_none(),
+            (Self::Rules(rules), Some(value)) => rules.matches(value),
+            (_, None) => false,
+        }
+    }
+}
+
+// Predicado sobre um campo do corpo JSON, como "$.user.id" ou "items[0].sku"
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BodyMatcher {
+    path: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    equal_to: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    contains: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    matches: Option<Pattern>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    absent: bool,
+}
+
+impl BodyMatcher {
+    fn matches(&self, body: Option<&Value>) -> bool {
+        let value = body.and_then(|body| json_path(body, &self.path));
+        if self.absent {
+            return value.is_none();
+        }
+        let Some(value) = value else {
+            return false;
+        };
+        
+        let text = match value {
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        };
+        self.equal_to.as_ref().is_none_or(|expected| value == expected)
+            && self.contains.as_ref().is_none_or(|part| text.contains(part.as_str()))
+            && self.matches.as_ref().is_none_or(|pattern| pattern.0.is_match(&text))
+    }
+}
+
+// Caminho simples em um JSON: "$", "user.name", "$.items[0].sku"
+pub fn json_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    let path = path.trim().trim_start_matches('$').replace('[', ".").replace(']', "");
+    
+    path.split('.')
+        .filter(|segment| !segment.is_empty())
+        .try_fold(value, |value, segment| match value {
+            Value::Object(map) => map.get(segment),
+            Value::Array(items) => items.get(segment.parse::<usize>().ok()?),
+            _ => None,
+        })
+}
+
+// "/users/:id/orders/*" ou "/files/**"; ":nome" captura um segmento, "*" aceita um e "**" o resto
+fn match_path(pattern: &str, path: &str) -> Option<HashMap<String, String>> {
+    let mut captures = HashMap::new();
+    let mut segments = path.trim_start_matches('/').split('/');
+    
+    for expected in pattern.trim_start_matches('/').split('/') {
+        if expected == "**" {
+            return Some(captures);
+        }
+        let segment = segments.next()?;
+        match expected.strip_prefix(':') {
+            Some(name) if !segment.is_empty() => {
+                captures.insert(name.to_string(), segment.to_string());
+            }
+            Some(_) => return None,
+            None if expected == "*" && !segment.is_empty() => {}
+            None if expected == segment => {}
+            None => return None,
+        }
+    }
+    
+    segments.next().is_none().then_some(captures)
+}
+
+// Critério de uma requisição; também usado para consultar o journal
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RequestPattern {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    method: Option<String>,
+    path: String,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    query: HashMap<String, TextMatcher>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    headers: HashMap<String, TextMatcher>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    body: Vec<BodyMatcher>,
+}
+
+impl RequestPattern {
+    pub fn parse(value: Value) -> Result<Self, String> {
+        let mut pattern: RequestPattern = serde_json::from_value(value).map_err(|e| e.to_string())?;
+        pattern.validate()?;
+        Ok(pattern)
+    }
+
+    fn validate(&mut self) -> Result<(), String> {
+        if !self.path.starts_with('/') {
+            return Err("request.path must start with '/'".to_string());
+        }
+        if let Some(method) = self.method.as_mut() {
+            *method = method.to_uppercase();
+            if method != "ANY" && Method::from_bytes(method.as_bytes()).is_err() {
+                return Err(format!("Invalid method '{}'", method));
+            }
+        }
+        if let Some(name) = self.headers.keys().find(|name| HeaderName::try_from(name.as_str()).is_err()) {
+            return Err(format!("Invalid header name '{}'", name));
+        }
+        Ok(())
+    }
+
+    // Método e caminho; o resto só é avaliado depois de ler o corpo
+    pub fn matches_route(&self, method: &Method, path: &str) -> Option<HashMap<String, String>> {
+        let method_matches = self
+            .method
+            .as_deref()
+            .is_none_or(|expected| expected == "ANY" || expected == method.as_str());
+        method_matches.then(|| match_path(&self.path, path)).flatten()
+    }
+
+    pub fn matches_request(&self, request: &MockRequest) -> bool {
+        self.query
+            .iter()
+            .all(|(name, matcher)| matcher.matches(request.query.get(name).map(String::as_str)))
+            && self.headers.iter().all(|(name, matcher)| {
+                matcher.matches(request.headers.get(name.as_str()).and_then(|v| v.to_str().ok()))
+            })
+            && self.body.iter().all(|matcher| matcher.matches(request.json.as_ref()))
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ResponseDefinition {
+    #[serde(default = "default_status")]
+    status: u16,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    headers: HashMap<String, String>,
+    // Texto é enviado como text/plain; qualquer outro valor vira JSON. Ambos aceitam {{...}}
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    body: Option<Value>,
+    // Bytes enviados como estão, sem template; o Content-Type fica a cargo de `headers`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    base64_body: Option<String>,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    delay_ms: u64,
+}
+
+fn default_status() -> u16 {
+    200
+}
+
+fn default_priority() -> u32 {
+    DEFAULT_PRIORITY
+}
+
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
+
+// Stub registrado: se a requisição casar com `request`, responde com `response`
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Mapping {
+    #[serde(default)]
+    id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    // Menor valor vence; no empate, vence o mais recente
+    #[serde(default = "default_priority")]
+    priority: u32,
+    request: RequestPattern,
+    response: ResponseDefinition,
+    // Máquina de estados: só casa no estado exigido e, ao casar, leva o cenário ao novo estado
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scenario: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    required_state: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    new_state: Option<String>,
+}
+
+impl Mapping {
+    fn parse(value: Value) -> Result<Self, String> {
+        let mut mapping: Mapping = serde_json::from_value(value).map_err(|e| e.to_string())?;
+        mapping.validate()?;
+        Ok(mapping)
+    }
+
+    fn validate(&mut self) -> Result<(), String> {
+        if self.id.trim().is_empty() {
+            self.id = uuid::Uuid::new_v4().to_string();
+        }
+        
+        if self.scenario.is_none() && (self.required_state.is_some() || self.new_state.is_some()) {
+            return Err("required_state and new_state need a scenario".to_string());
+        }
+        if self.scenario.as_ref().is_some_and(|name| name.trim().is_empty()) {
+            return Err("scenario must not be empty".to_string());
+        }
+        
+        self.request.validate()?;
+        if self.request.path.starts_with(ADMIN_PREFIX) {
+            return Err(format!("Paths under {} are reserved", ADMIN_PREFIX));
+        }
+        
+        let response = &self.response;
+        // Só respostas finais: 1xx e códigos acima de 599 confundem os clientes
+        if !(200..=599).contains(&response.status) {
+            return Err(format!("Invalid status code {} (expected 200-599)", response.status));
+        }
+        for (name, value) in &response.headers {
+            if HeaderName::try_from(name.as_str()).is_err() || HeaderValue::try_from(value.as_str()).is_err() {
+                return Err(format!("Invalid response header '{}'", name));
+            }
+            template::validate(value)?;
+        }
+        if let Some(body) = &response.body {
+            template::validate_value(body)?;
+        }
+        if let Some(encoded) = &response.base64_body {
+            if response.body.is_some() {
+                return Err("Use either body or base64_body, not both".to_string());
+            }
+            general_purpose::STANDARD
+                .decode(encoded)
+                .map_err(|e| format!("Invalid base64_body: {}", e))?;
+        }
+        if response.delay_ms > MAX_DELAY * 1000 {
+            return Err(format!("Maximum delay is {} seconds", MAX_DELAY));
+        }
+        Ok(())
+    }
+
+    fn allowed_in(&self, states: &HashMap<String, String>) -> bool {
+        match (&self.scenario, &self.required_state) {
+            (Some(scenario), Some(required)) => {
+                states.get(scenario).map(String::as_str).unwrap_or(STARTED) == required
+            }
+            _ => true,
+        }
+    }
+
+    fn respond(&self, context: &Context) -> Response {
+        let definition = &self.response;
+        let status = StatusCode::from_u16(definition.status).unwrap_or(StatusCode::OK);
+        
+        let (content_type, body) = match &definition.body {
+            None => match &definition.base64_body {
+                Some(encoded) => (None, Body::from(general_purpose::STANDARD.decode(encoded).unwrap_or_default())),
+                None => (None, Body::empty()),
+            },
+            Some(Value::String(text)) => (
+                Some("text/plain; charset=utf-8"),
+                Body::from(template::render(text, context)),
+            ),
+            Some(value) => (
+                Some("application/json"),
+                Body::from(template::render_value(value, context).to_string()),
+            ),
+        };
+        
+        let mut response = (status, body).into_response();
+        let headers = response.headers_mut();
+        if let Some(content_type) = content_type {
+            headers.insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+        }
+        // Nomes validados no cadastro; valores gerados por template podem não ser válidos
+        for (name, value) in &definition.headers {
+            let value = template::render(value, context);
+            if let (Ok(name), Ok(value)) = (HeaderName::try_from(name.as_str()), HeaderValue::try_from(value)) {
+                headers.insert(name, value);
+            }
+        }
+        response
+    }
+}
+
+// Dados da requisição usados pelos predicados
+pub struct MockRequest {
+    pub query: HashMap<String, String>,
+    pub headers: HeaderMap,
+    pub json: Option<Value>,
+}
+
+// Anexado à resposta de um mapeamento, para o journal saber quem respondeu
+#[derive(Clone)]
+pub struct MockMatch(pub String);
+
+// Arquivos .json do diretório, em ordem alfabética
+fn load_dir(dir: &std::path::Path) -> Result<Vec<Mapping>, String> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| format!("{}: {}", dir.display(), e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+    
+    let mut loaded = Vec::new();
+    for file in &files {
+        let parse = || -> Result<Vec<Mapping>, String> {
+            let raw = std::fs::read(file).map_err(|e| e.to_string())?;
+            let value: Value = serde_json::from_slice(&raw).map_err(|e| e.to_string())?;
+            
+            // Um mapeamento, uma lista ou {"mappings": [...]}
+            let values = match value {
+                Value::Array(values) => values,
+                Value::Object(mut object) if object.contains_key("mappings") => {
+                    match object.remove("mappings") {
+                        Some(Value::Array(values)) => values,
+                        _ => return Err("\"mappings\" must be an array".to_string()),
+                    }
+                }
+                value => vec![value],
+            };
+            values.into_iter().map(Mapping::parse).collect()
+        };
+        loaded.extend(parse().map_err(|e| format!("{}: {}", file.display(), e))?);
+    }
+    
+    tracing::info!("🎭 {} mocks carregados de {}", loaded.len(), dir.display());
+    Ok(loaded)
+}
+
+// Mapeamentos ativos; os carregados do diretório e do HAR voltam em um reset
+#[derive(Clone, Default)]
+pub struct MockStore {
+    mappings: Arc<Mutex<Vec<Mapping>>>,
+    loaded: Arc<Vec<Mapping>>,
+    // Requisições atendidas por mapeamento ({{counter}}) e contadores nomeados
+    hits: Arc<Mutex<HashMap<String, u64>>>,
+    counters: Counters,
+    // Estado atual de cada cenário; ausente equivale a STARTED
+    scenarios: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl MockStore {
+    pub fn load(config: &MockConfig) -> Result<Self, String> {
+        let mut loaded = match &config.dir {
+            Some(dir) => load_dir(dir)?,
+            None => Vec::new(),
+        };
+        
+        if let Some(file) = &config.har {
+            let imported = har::import(file)
+                .and_then(|values| values.into_iter().map(Mapping::parse).collect::<Result<Vec<_>, _>>())
+                .map_err(|e| format!("{}: {}", file.display(), e))?;
+            tracing::info!("📦 {} mocks importados de {}", imported.len(), file.display());
+            loaded.extend(imported);
+        }
+        
+        Ok(Self {
+            mappings: Arc::new(Mutex::new(loaded.clone())),
+            loaded: Arc::new(loaded),
+            ..Self::default()
+        })
+    }
+
+    fn list(&self) -> Vec<Mapping> {
+        self.mappings.lock().map(|m| m.clone()).unwrap_or_default()
+    }
+
+    fn add(&self, mapping: Mapping) {
+        if let Ok(mut mappings) = self.mappings.lock() {
+            mappings.retain(|m| m.id != mapping.id);
+            mappings.push(mapping);
+        }
+    }
+
+    fn remove(&self, id: &str) -> Option<Mapping> {
+        let mut mappings = self.mappings.lock().ok()?;
+        let index = mappings.iter().position(|m| m.id == id)?;
+        Some(mappings.remove(index))
+    }
+
+    // Também zera os contadores dos templates e os cenários
+    fn replace_all(&self, with: Vec<Mapping>) {
+        if let Ok(mut mappings) = self.mappings.lock() {
+            *mappings = with;
+        }
+        if let Ok(mut hits) = self.hits.lock() {
+            hits.clear();
+        }
+        self.counters.reset();
+        self.reset_scenarios(None);
+    }
+
+    fn hit(&self, id: &str) -> u64 {
+        self.hits
+            .lock()
+            .map(|mut hits| {
+                let count = hits.entry(id.to_string()).or_insert(0);
+                *count += 1;
+                *count
+            })
+            .unwrap_or(0)
+    }
+
+    // Candidatos por método e caminho, na ordem em que devem ser avaliados
+    fn candidates(&self, method: &Method, path: &str) -> Vec<(Mapping, HashMap<String, String>)> {
+        let mut candidates: Vec<_> = self
+            .list()
+            .into_iter()
+            .rev()
+            .filter_map(|m| m.request.matches_route(method, path).map(|captures| (m, captures)))
+            .collect();
+        candidates.sort_by_key(|(m, _)| m.priority);
+        candidates
+    }
+
+    // Escolha e transição sob o mesmo lock, para que requisições concorrentes vejam estados em sequência
+    fn select(
+        &self,
+        candidates: Vec<(Mapping, HashMap<String, String>)>,
+        request: &MockRequest,
+    ) -> Option<(Mapping, HashMap<String, String>)> {
+        let mut states = self.scenarios.lock().ok()?;
+        let selected = candidates
+            .into_iter()
+            .find(|(m, _)| m.allowed_in(&states) && m.request.matches_request(request))?;
+        
+        if let (Some(scenario), Some(new_state)) = (&selected.0.scenario, &selected.0.new_state) {
+            tracing::debug!("🎬 Cenário {}: {}", scenario, new_state);
+            states.insert(scenario.clone(), new_state.clone());
+        }
+        Some(selected)
+    }
+
+    // Cenários citados pelos mapeamentos, com os estados que eles conhecem
+    fn scenarios(&self) -> BTreeMap<String, BTreeSet<String>> {
+        let mut scenarios: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        for mapping in self.list() {
+            if let Some(name) = mapping.scenario {
+                let states = scenarios.entry(name).or_default();
+                states.insert(STARTED.to_string());
+                states.extend(mapping.required_state);
+                states.extend(mapping.new_state);
+            }
+        }
+        scenarios
+    }
+
+    fn scenario_state(&self, name: &str) -> String {
+        self.scenarios
+            .lock()
+            .ok()
+            .and_then(|states| states.get(name).cloned())
+            .unwrap_or_else(|| STARTED.to_string())
+    }
+
+    fn set_scenario_state(&self, name: &str, state: &str) {
+        if let Ok(mut states) = self.scenarios.lock() {
+            states.insert(name.to_string(), state.to_string());
+        }
+    }
+
+    // Sem nome, volta todos os cenários ao estado inicial
+    fn reset_scenarios(&self, name: Option<&str>) {
+        if let Ok(mut states) = self.scenarios.lock() {
+            match name {
+                Some(name) => {
+                    states.remove(name);
+                }
+                None => states.clear(),
+            }
+        }
+    }
+}
+
+pub async fn layer(State(state): State<Arc<AppState>>, request: Request, next: Next) -> Response {
+    let path = request.uri().path().to_string();
+    if path.starts_with(ADMIN_PREFIX) {
+        return next.run(request).await;
+    }
+    
+    // Métricas pelo padrão do mapeamento ("/users/:id"), não pelo caminho cru
+    let candidates = state.mocks.candidates(request.method(), &path);
+    let Some(pattern) = candidates.first().map(|(mapping, _)| mapping.request.path.clone()) else {
+        return next.run(request).await;
+    };
+    
+    let (parts, body) = request.into_parts();
+    let bytes = match axum::body::to_bytes(body, MAX_BODY).await {
+        Ok(bytes) => bytes,
+        Err(_) => {
+            state.metrics.increment_total();
+            state.metrics.record_endpoint(pattern);
+            state.metrics.increment_failed();
+            return (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                Json(json!({
+                    "error": "Request body too large",
+                    "max_bytes": MAX_BODY
+                })),
+            )
+                .into_response();
+        }
+    };
+    
+    let mock_request = MockRequest {
+        query: parts
+            .uri
+            .query()
+            .and_then(|q| serde_urlencoded::from_str(q).ok())
+            .unwrap_or_default(),
+        headers: parts.headers.clone(),
+        json: serde_json::from_slice(&bytes).ok(),
+    };
+    
+    let Some((mapping, captures)) = state.mocks.select(candidates, &mock_request) else {
+        return next.run(Request::from_parts(parts, Body::from(bytes))).await;
+    };
+    
+    state.metrics.increment_total();
+    state.metrics.record_endpoint(mapping.request.path.clone());
+    if mapping.response.status < 400 {
+        state.metrics.increment_success();
+    } else {
+        state.metrics.increment_failed();
+    }
+    tracing::debug!("🎭 Mock {} respondeu {} {}", mapping.id, parts.method, path);
+    
+    // Os mesmos dados (já ocultados) que os endpoints de eco mostram
+    let query = Query(mock_request.query);
+    let mut info = extract_request_info(&state.config.redaction, &parts.headers, query, Some(bytes));
+    info.method = Some(parts.method.to_string());
+    
+    let counter = state.mocks.hit(&mapping.id);
+    let mut response = mapping.respond(&Context::new(&info, &path, &captures, counter, &state.mocks.counters));
+    response.extensions_mut().insert(MockMatch(mapping.id.clone()));
+    
+    if mapping.response.delay_ms > 0 {
+        tokio::time::sleep(Duration::from_millis(mapping.response.delay_ms)).await;
+    }
+    response
+}
+
+pub fn routes(state: &Arc<AppState>) -> Router<Arc<AppState>> {
+    if !state.config.mocks.enabled {
+        return Router::new();
+    }
+    
+    Router::new()
+        .route(
+            "/__admin/mappings",
+            get(handle_list).post(handle_create).delete(handle_clear),
+        )
+        .route("/__admin/mappings/reset", post(handle_reset))
+        .route("/__admin/mappings/:id", get(handle_get).delete(handle_delete))
+        .route("/__admin/scenarios", get(handle_scenarios))
+        .route("/__admin/scenarios/reset", post(handle_scenarios_reset))
+        .route("/__admin/scenarios/:name/state", put(handle_scenario_state))
+        .route("/__admin/scenarios/:name/reset", post(handle_scenario_reset))
+}
+
+fn not_found(id: &str) -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(json!({
+            "error": "Mapping not found",
+            "id": id
+        })),
+    )
+        .into_response()
+}
+
+async fn handle_list(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/__admin/mappings".to_string());
+    state.metrics.increment_success();
+    
+    let mappings = state.mocks.list();
+    Json(json!({
+        "total": mappings.len(),
+        "mappings": mappings
+    }))
+}
+
+async fn handle_create(State(state): State<Arc<AppState>>, body: Bytes) -> Response {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/__admin/mappings".to_string());
+    
+    let mapping = serde_json::from_slice(&body)
+        .map_err(|e| e.to_string())
+        .and_then(Mapping::parse);
+    
+    match mapping {
+        Ok(mapping) => {
+            state.metrics.increment_success();
+            tracing::info!("🎭 Mock registrado: {} {}", mapping.id, mapping.request.path);
+            state.mocks.add(mapping.clone());
+            (StatusCode::CREATED, Json(mapping)).into_response()
+        }
+        Err(message) => {
+            state.metrics.increment_failed();
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": "Invalid mapping",
+                    "message": message
+                })),
+            )
+                .into_response()
+        }
+    }
+}
+
+async fn handle_get(State(state): State<Arc<AppState>>, Path(id): Path<String>) -> Response {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/__admin/mappings/:id".to_string());
+    
+    match state.mocks.list().into_iter().find(|m| m.id == id) {
+        Some(mapping) => {
+            state.metrics.increment_success();
+            Json(mapping).into_response()
+        }
+        None => {
+            state.metrics.increment_failed();
+            not_found(&id)
+        }
+    }
+}
+
+async fn handle_delete(State(state): State<Arc<AppState>>, Path(id): Path<String>) -> Response {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/__admin/mappings/:id".to_string());
+    
+    match state.mocks.remove(&id) {
+        Some(mapping) => {
+            state.metrics.increment_success();
+            Json(json!({ "deleted": mapping })).into_response()
+        }
+        None => {
+            state.metrics.increment_failed();
+            not_found(&id)
+        }
+    }
+}
+
+// Remove todos os mapeamentos, inclusive os carregados do diretório
+async fn handle_clear(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/__admin/mappings".to_string());
+    state.metrics.increment_success();
+    
+    let deleted = state.mocks.list().len();
+    state.mocks.replace_all(Vec::new());
+    Json(json!({ "deleted": deleted }))
+}
+
+// Volta ao conjunto carregado do diretório na inicialização
+async fn handle_reset(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/__admin/mappings/reset".to_string());
+    state.metrics.increment_success();
+    
+    state.mocks.replace_all(state.mocks.loaded.to_vec());
+    Json(json!({ "total": state.mocks.loaded.len() }))
+}
+
+async fn handle_scenarios(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/__admin/scenarios".to_string());
+    state.metrics.increment_success();
+    
+    let scenarios: Vec<_> = state
+        .mocks
+        .scenarios()
+        .into_iter()
+        .map(|(name, possible_states)| {
+            json!({
+                "state": state.mocks.scenario_state(&name),
+                "name": name,
+                "possible_states": possible_states
+            })
+        })
+        .collect();
+    Json(json!({ "scenarios": scenarios }))
+}
+
+#[derive(Deserialize)]
+struct ScenarioState {
+    state: String,
+}
+
+// Força um estado, por exemplo para começar um teste já no meio da sequência
+async fn handle_scenario_state(
+    State(state): State<Arc<AppState>>,
+    Path(name): Path<String>,
+    body: Bytes,
+) -> Response {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/__admin/scenarios/:name/state".to_string());
+    
+    let Some(possible_states) = state.mocks.scenarios().remove(&name) else {
+        state.metrics.increment_failed();
+        return scenario_not_found(&name);
+    };
+    
+    let new_state = match serde_json::from_slice::<ScenarioState>(&body) {
+        Ok(ScenarioState { state }) if possible_states.contains(&state) => state,
+        Ok(ScenarioState { state: unknown }) => {
+            state.metrics.increment_failed();
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": "Unknown scenario state",
+                    "state": unknown,
+                    "possible_states": possible_states
+                })),
+            )
+                .into_response();
+        }
+        Err(e) => {
+            state.metrics.increment_failed();
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": "Invalid request body",
+                    "message": e.to_string()
+                })),
+            )
+                .into_response();
+        }
+    };
+    
+    state.metrics.increment_success();
+    state.mocks.set_scenario_state(&name, &new_state);
+    Json(json!({ "name": name, "state": new_state })).into_response()
+}
+
+async fn handle_scenario_reset(State(state): State<Arc<AppState>>, Path(name): Path<String>) -> Response {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/__admin/scenarios/:name/reset".to_string());
+    
+    if !state.mocks.scenarios().contains_key(&name) {
+        state.metrics.increment_failed();
+        return scenario_not_found(&name);
+    }
+    
+    state.metrics.increment_success();
+    state.mocks.reset_scenarios(Some(&name));
+    Json(json!({ "name": name, "state": STARTED })).into_response()
+}
+
+async fn handle_scenarios_reset(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/__admin/scenarios/reset".to_string());
+    state.metrics.increment_success();
+    
+    state.mocks.reset_scenarios(None);
+    Json(json!({ "state": STARTED }))
+}
+
+fn scenario_not_found(name: &str) -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(json!({
+            "error": "Scenario not found",
+            "name": name
+        })),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern(value: Value) -> RequestPattern {
+        RequestPattern::parse(value).unwrap()
+    }
+
+    fn request(query: &[(&str, &str)], headers: &[(&'static str, &'static str)], json: Option<Value>) -> MockRequest {
+        let mut header_map = HeaderMap::new();
+        for (name, value) in headers {
+            header_map.insert(*name, value.parse().unwrap());
+        }
+        MockRequest {
+            query: query.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            headers: header_map,
+            json,
+        }
+    }
+
+    #[test]
+    fn parse_rejects_invalid_patterns() {
+        assert!(RequestPattern::parse(json!({ "path": "users" })).is_err());
+        assert!(RequestPattern::parse(json!({ "path": "/", "method": "GE T" })).is_err());
+        assert!(RequestPattern::parse(json!({ "path": "/", "headers": { "bad header": "x" } })).is_err());
+        assert!(RequestPattern::parse(json!({ "path": "/", "query": { "q": { "matches": "(" } } })).is_err());
+        assert!(RequestPattern::parse(json!({ "path": "/", "query": { "q": { "equals": "x" } } })).is_err());
+        assert!(RequestPattern::parse(json!({ "path": "/", "extra": true })).is_err());
+    }
+
+    #[test]
+    fn mapping_status_must_be_final() {
+        let mapping = |status: u16| Mapping::parse(json!({ "request": { "path": "/" }, "response": { "status": status } }));
+        assert!(mapping(200).is_ok());
+        assert!(mapping(599).is_ok());
+        assert!(mapping(101).is_err());
+        assert!(mapping(600).is_err());
+    }
+
+    #[test]
+    fn match_path_captures_and_wildcards() {
+        let captures = match_path("/users/:id/orders/*", "/users/42/orders/7").unwrap();
+        assert_eq!(captures.get("id").map(String::as_str), Some("42"));
+        assert!(match_path("/users/:id", "/users/").is_none());
+        assert!(match_path("/users/:id", "/users/42/orders").is_none());
+        assert!(match_path("/files/*", "/files/").is_none());
+        assert!(match_path("/files/**", "/files/a/b/c").is_some());
+        assert!(match_path("/exact", "/other").is_none());
+    }
+
+    #[test]
+    fn matches_route_checks_method() {
+        let get = pattern(json!({ "method": "get", "path": "/items/:id" }));
+        assert!(get.matches_route(&Method::GET, "/items/1").is_some());
+        assert!(get.matches_route(&Method::POST, "/items/1").is_none());
+        
+        let any = pattern(json!({ "method": "ANY", "path": "/items/:id" }));
+        assert!(any.matches_route(&Method::DELETE, "/items/1").is_some());
+        
+        let unset = pattern(json!({ "path": "/items/:id" }));
+        assert!(unset.matches_route(&Method::PUT, "/items/1").is_some());
+    }
+
+    #[test]
+    fn matches_request_query_and_headers() {
+        let criteria = pattern(json!({
+            "path": "/search",
+            "query": {
+                "q": "rust",
+                "page": { "matches": "^[0-9]+$" },
+                "debug": { "absent": true }
+            },
+            "headers": { "Accept": { "contains": "json" } }
+        }));
+        
+        let accept = [("accept", "application/json")];
+        assert!(criteria.matches_request(&request(&[("q", "rust"), ("page", "2")], &accept, None)));
+        assert!(!criteria.matches_request(&request(&[("q", "go"), ("page", "2")], &accept, None)));
+        assert!(!criteria.matches_request(&request(&[("q", "rust"), ("page", "x")], &accept, None)));
+        assert!(!criteria.matches_request(&request(&[("q", "rust"), ("page", "2"), ("debug", "1")], &accept, None)));
+        assert!(!criteria.matches_request(&request(&[("q", "rust"), ("page", "2")], &[("accept", "text/html")], None)));
+        assert!(!criteria.matches_request(&request(&[("q", "rust"), ("page", "2")], &[], None)));
+    }
+
+    #[test]
+    fn matches_request_body() {
+        let criteria = pattern(json!({
+            "path": "/orders",
+            "body": [
+                { "path": "$.user.id", "equal_to": 7 },
+                { "path": "items[0].sku", "matches": "^SKU-" },
+                { "path": "coupon", "absent": true }
+            ]
+        }));
+        
+        let body = json!({ "user": { "id": 7 }, "items": [{ "sku": "SKU-1" }] });
+        assert!(criteria.matches_request(&request(&[], &[], Some(body.clone()))));
+        
+        let mut with_coupon = body.clone();
+        with_coupon["coupon"] = json!("FREE");
+        assert!(!criteria.matches_request(&request(&[], &[], Some(with_coupon))));
+        
+        // "7" em texto não é igual ao número 7
+        let text_id = json!({ "user": { "id": "7" }, "items": [{ "sku": "SKU-1" }] });
+        assert!(!criteria.matches_request(&request(&[], &[], Some(text_id))));
+        assert!(!criteria.matches_request(&request(&[], &[], None)));
+    }
+
+    #[test]
+    fn json_path_walks_objects_and_arrays() {
+        let value = json!({ "items": [{ "sku": "a" }, { "sku": "b" }] });
+        assert_eq!(json_path(&value, "$.items[1].sku"), Some(&json!("b")));
+        assert_eq!(json_path(&value, "items.0.sku"), Some(&json!("a")));
+        assert_eq!(json_path(&value, "$"), Some(&value));
+        assert_eq!(json_path(&value, "items[5]"), None);
+        assert_eq!(json_path(&value, "items.sku"), None);
+    }
+}
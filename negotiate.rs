@@ -0,0 +1,257 @@
+use serde_json::json;
+
+// Variantes do recurso /negotiate, na ordem de preferência do servidor
+pub const MEDIA_TYPES: [&str; 4] = ["application/json", "text/html", "application/xml", "text/plain"];
+pub const LANGUAGES: [&str; 2] = ["en", "pt-BR"];
+pub const CHARSETS: [&str; 2] = ["utf-8", "iso-8859-1"];
+
+// Headers que influenciam a escolha e devem ir no Vary
+pub const VARY: &str = "Accept, Accept-Language, Accept-Charset";
+
+// Item de um header Accept*: valor e seu peso (q)
+struct Preference {
+    value: String,
+    q: f32,
+}
+
+fn parse_preferences(header: &str) -> Vec<Preference> {
+    header
+        .split(',')
+        .filter_map(|item| {
+            let mut parts = item.split(';');
+            let value = parts.next()?.trim().to_lowercase();
+            if value.is_empty() {
+                return None;
+            }
+            
+            let q = parts
+                .filter_map(|param| param.trim().split_once('='))
+                .find(|(name, _)| name.trim().eq_ignore_ascii_case("q"))
+                .and_then(|(_, value)| value.trim().parse::<f32>().ok())
+                .unwrap_or(1.0)
+                .clamp(0.0, 1.0);
+            
+            Some(Preference { value, q })
+        })
+        .collect()
+}
+
+// Quão especificamente uma faixa de mídia cobre um tipo concreto (None se não cobre)
+fn media_type_specificity(range: &str, media_type: &str) -> Option<u8> {
+    let (kind, subtype) = media_type.split_once('/').unwrap_or((media_type, ""));
+    let (range_kind, range_subtype) = range.split_once('/')?;
+    
+    match (range_kind.trim(), range_subtype.trim()) {
+        (t, s) if t == kind && s == subtype => Some(2),
+        (t, "*") if t == kind => Some(1),
+        ("*", "*") => Some(0),
+        _ => None,
+    }
+}
+
+// Filtragem básica da RFC 4647: "en" cobre "en-US", "*" cobre qualquer idioma
+fn language_specificity(range: &str, language: &str) -> Option<u8> {
+    if range == language {
+        Some(2)
+    } else if language.starts_with(range) && language[range.len()..].starts_with('-') {
+        Some(1)
+    } else if range == "*" {
+        Some(0)
+    } else {
+        None
+    }
+}
+
+fn charset_specificity(range: &str, charset: &str) -> Option<u8> {
+    match range {
+        r if r == charset => Some(1),
+        "*" => Some(0),
+        _ => None,
+    }
+}
+
+// Escolhe entre os valores disponíveis (em ordem de preferência do servidor) o de maior q,
+// usando para cada um a preferência mais específica que o cobre.
+// Sem o header, qualquer valor serve.
+fn best_match<'a>(
+    header: Option<&str>,
+    available: &[&'a str],
+    specificity: fn(&str, &str) -> Option<u8>,
+) -> Option<&'a str> {
+    let Some(header) = header.filter(|h| !h.trim().is_empty()) else {
+        return available.first().copied();
+    };
+    
+    let preferences = parse_preferences(header);
+    
+    available
+        .iter()
+        .map(|value| {
+            let lowered = value.to_lowercase();
+            let q = preferences
+                .iter()
+                .filter_map(|p| specificity(&p.value, &lowered).map(|s| (s, p.q)))
+                .max_by_key(|(s, _)| *s)
+                .map(|(_, q)| q)
+                .unwrap_or(0.0);
+            (*value, q)
+        })
+        .filter(|(_, q)| *q > 0.0)
+        .fold(None, |best: Option<(&str, f32)>, candidate| match best {
+            Some((_, q)) if q >= candidate.1 => best,
+            _ => Some(candidate),
+        })
+        .map(|(value, _)| value)
+}
+
+pub fn best_media_type<'a>(accept: Option<&str>, available: &[&'a str]) -> Option<&'a str> {
+    best_match(accept, available, media_type_specificity)
+}
+
+pub fn best_language<'a>(accept_language: Option<&str>, available: &[&'a str]) -> Option<&'a str> {
+    best_match(accept_language, available, language_specificity)
+}
+
+pub fn best_charset<'a>(accept_charset: Option<&str>, available: &[&'a str]) -> Option<&'a str> {
+    best_match(accept_charset, available, charset_specificity)
+}
+
+// Representação escolhida para /negotiate
+pub struct Variant {
+    pub media_type: &'static str,
+    pub language: &'static str,
+    pub charset: &'static str,
+}
+
+impl Variant {
+    pub fn select(
+        accept: Option<&str>,
+        accept_language: Option<&str>,
+        accept_charset: Option<&str>,
+    ) -> Option<Self> {
+        Some(Self {
+            media_type: best_media_type(accept, &MEDIA_TYPES)?,
+            language: best_language(accept_language, &LANGUAGES)?,
+            charset: best_charset(accept_charset, &CHARSETS)?,
+        })
+    }
+
+    pub fn content_type(&self) -> String {
+        format!("{}; charset={}", self.media_type, self.charset)
+    }
+
+    pub fn render(&self) -> Vec<u8> {
+        let (title, message) = match self.language {
+            "pt-BR" => ("Negociação de conteúdo", "Olá! Esta é a representação em português."),
+            _ => ("Content negotiation", "Hello! This is the English representation."),
+        };
+        
+        let body = match self.media_type {
+            "application/json" => serde_json::to_string_pretty(&json!({
+                "title": title,
+                "message": message,
+                "media_type": self.media_type,
+                "language": self.language,
+                "charset": self.charset
+            }))
+            .unwrap_or_default(),
+            "text/html" => format!(
+                r#"<!DOCTYPE html>
+<html lang="{lang}">
+<head>
+    <meta charset="{charset}">
+    <title>{title}</title>
+</head>
+<body>
+    <h1>{title}</h1>
+    <p>{message}</p>
+</body>
+</html>"#,
+                lang = self.language,
+                charset = self.charset,
+            ),
+            "application/xml" => format!(
+                r#"<?xml version="1.0" encoding="{charset}"?>
+<negotiation xml:lang="{lang}">
+    <title>{title}</title>
+    <message>{message}</message>
+</negotiation>"#,
+                lang = self.language,
+                charset = self.charset.to_uppercase(),
+            ),
+            _ => format!("{}\n\n{}\n", title, message),
+        };
+        
+        match self.charset {
+            // Todos os textos acima cabem em Latin-1
+            "iso-8859-1" => body.chars().map(|c| u8::try_from(c).unwrap_or(b'?')).collect(),
+            _ => body.into_bytes(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_or_empty_header_picks_server_preference() {
+        assert_eq!(best_media_type(None, &MEDIA_TYPES), Some("application/json"));
+        assert_eq!(best_media_type(Some("  "), &MEDIA_TYPES), Some("application/json"));
+    }
+
+    #[test]
+    fn highest_q_wins_and_ties_keep_server_order() {
+        assert_eq!(
+            best_media_type(Some("text/html;q=0.5, application/xml;q=0.9"), &MEDIA_TYPES),
+            Some("application/xml")
+        );
+        assert_eq!(
+            best_media_type(Some("text/plain, text/html"), &MEDIA_TYPES),
+            Some("text/html")
+        );
+    }
+
+    #[test]
+    fn most_specific_range_decides_the_q() {
+        // text/html vem excluído explicitamente, mesmo coberto por text/*
+        assert_eq!(
+            best_media_type(Some("text/*, text/html;q=0"), &MEDIA_TYPES),
+            Some("text/plain")
+        );
+        assert_eq!(
+            best_media_type(Some("*/*;q=0.1, application/xml"), &MEDIA_TYPES),
+            Some("application/xml")
+        );
+    }
+
+    #[test]
+    fn nothing_acceptable_returns_none() {
+        assert_eq!(best_media_type(Some("image/png"), &MEDIA_TYPES), None);
+        assert_eq!(best_media_type(Some("*/*;q=0"), &MEDIA_TYPES), None);
+        assert_eq!(best_charset(Some("utf-16"), &CHARSETS), None);
+    }
+
+    #[test]
+    fn malformed_q_values_are_tolerated() {
+        assert_eq!(
+            best_media_type(Some("text/html;q=abc, application/json;q=5"), &MEDIA_TYPES),
+            Some("application/json")
+        );
+    }
+
+    #[test]
+    fn language_prefixes_match_subtags() {
+        assert_eq!(best_language(Some("pt"), &LANGUAGES), Some("pt-BR"));
+        assert_eq!(best_language(Some("PT-br, en;q=0.8"), &LANGUAGES), Some("pt-BR"));
+        assert_eq!(best_language(Some("p"), &LANGUAGES), None);
+        assert_eq!(best_language(Some("*;q=0.5, en;q=0.1"), &LANGUAGES), Some("pt-BR"));
+    }
+
+    #[test]
+    fn variant_requires_every_dimension() {
+        let variant = Variant::select(Some("text/plain"), Some("en"), Some("iso-8859-1")).unwrap();
+        assert_eq!(variant.content_type(), "text/plain; charset=iso-8859-1");
+        assert!(Variant::select(None, Some("fr"), None).is_none());
+    }
+}
@@ -0,0 +1,1366 @@
+use axum::{
+    extract::State,
+    http::HeaderMap,
+    response::{Html, IntoResponse, Json},
+    routing::get,
+    Extension, Router,
+};
+use serde_json::{json, Map, Value};
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::mock::MAX_BODY;
+use crate::proxy::ProxyMode;
+use crate::tls::TlsInfo;
+use crate::{cookies, formats, images, negotiate, url_policy, AppState};
+use crate::{MAX_BYTES, MAX_DELAY, MAX_LINES, MAX_REDIRECTS, REDIRECT_STATUS_CODES};
+
+// Swagger UI servido do CDN, como o GraphiQL de /graphql
+const DOCS_PAGE: &str = r##"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta charset="utf-8">
+    <title>RustJin API</title>
+    <link rel="stylesheet" href="https://unpkg.com/swagger-ui-dist@5/swagger-ui.css">
+</head>
+<body>
+    <div id="swagger-ui"></div>
+    <script src="https://unpkg.com/swagger-ui-dist@5/swagger-ui-bundle.js"></script>
+    <script>
+        window.ui = SwaggerUIBundle({ url: "/openapi.json", dom_id: "#swagger-ui", deepLinking: true });
+    </script>
+</body>
+</html>"##;
+
+// Métodos aceitos pelas rotas registradas com any()
+const ANY_METHODS: [&str; 5] = ["get", "post", "put", "patch", "delete"];
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/openapi.json", get(handle_spec))
+        .route("/docs", get(handle_docs))
+}
+
+async fn handle_spec(
+    State(state): State<Arc<AppState>>,
+    headers: HeaderMap,
+    tls: Option<Extension<TlsInfo>>,
+) -> impl IntoResponse {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/openapi.json".to_string());
+    state.metrics.increment_success();
+    
+    Json(document(&state.config, &state.config.redirect.base_url(&headers, tls.is_some())))
+}
+
+async fn handle_docs(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/docs".to_string());
+    state.metrics.increment_success();
+    
+    Html(DOCS_PAGE)
+}
+
+// Schemas
+
+fn reference(name: &str) -> Value {
+    json!({ "$ref": format!("#/components/schemas/{}", name) })
+}
+
+fn string() -> Value {
+    json!({ "type": "string" })
+}
+
+fn integer(minimum: i64, maximum: Option<u64>) -> Value {
+    let mut schema = json!({ "type": "integer", "minimum": minimum });
+    if let Some(maximum) = maximum {
+        schema["maximum"] = json!(maximum);
+    }
+    schema
+}
+
+fn boolean() -> Value {
+    json!({ "type": "boolean", "default": false })
+}
+
+fn string_map() -> Value {
+    json!({ "type": "object", "additionalProperties": { "type": "string" } })
+}
+
+fn object(properties: Value) -> Value {
+    json!({ "type": "object", "properties": properties })
+}
+
+fn binary() -> Value {
+    json!({ "type": "string", "contentMediaType": "application/octet-stream" })
+}
+
+// Operação do documento, montada campo a campo
+struct Operation(Value);
+
+fn operation(tag: &str, summary: &str) -> Operation {
+    Operation(json!({ "tags": [tag], "summary": summary, "responses": {} }))
+}
+
+impl Operation {
+    fn describe(mut self, description: impl Into<String>) -> Self {
+        self.0["description"] = json!(description.into());
+        self
+    }
+
+    fn parameter(mut self, location: &str, name: &str, schema: Value, description: &str) -> Self {
+        let parameter = json!({
+            "name": name,
+            "in": location,
+            "required": location == "path",
+            "description": description,
+            "schema": schema
+        });
+        match self.0["parameters"].as_array_mut() {
+            Some(parameters) => parameters.push(parameter),
+            None => self.0["parameters"] = json!([parameter]),
+        }
+        self
+    }
+
+    fn path(self, name: &str, schema: Value, description: &str) -> Self {
+        self.parameter("path", name, schema, description)
+    }
+
+    fn query(self, name: &str, schema: Value, description: &str) -> Self {
+        self.parameter("query", name, schema, description)
+    }
+
+    fn header(self, name: &str, schema: Value, description: &str) -> Self {
+        self.parameter("header", name, schema, description)
+    }
+
+    // Torna obrigatório o último parâmetro adicionado
+    fn required(mut self) -> Self {
+        if let Some(parameter) = self.0["parameters"].as_array_mut().and_then(|p| p.last_mut()) {
+            parameter["required"] = json!(true);
+        }
+        self
+    }
+
+    // Chamado uma vez por tipo de mídia aceito
+    fn body(mut self, media_type: &str, schema: Value) -> Self {
+        self.0["requestBody"]["content"][media_type] = json!({ "schema": schema });
+        self
+    }
+
+    fn respond(mut self, status: u16, description: &str, content: &[(&str, Value)]) -> Self {
+        let response = &mut self.0["responses"][status.to_string()];
+        response["description"] = json!(description);
+        for (media_type, schema) in content {
+            response["content"][*media_type] = json!({ "schema": schema });
+        }
+        self
+    }
+
+    fn ok(self, media_type: &str, schema: Value) -> Self {
+        self.respond(200, "OK", &[(media_type, schema)])
+    }
+
+    fn json(self, schema: Value) -> Self {
+        self.ok("application/json", schema)
+    }
+
+    // Corpos de erro possíveis para um status, cada um como exemplo nomeado
+    fn error(mut self, status: u16, name: &str, example: Value) -> Self {
+        let response = &mut self.0["responses"][status.to_string()];
+        let description = match response["description"].as_str() {
+            Some(previous) => format!("{} / {}", previous, name),
+            None => name.to_string(),
+        };
+        response["description"] = json!(description);
+        response["content"]["application/json"]["schema"] = reference("Error");
+        response["content"]["application/json"]["examples"][name] = json!({ "value": example });
+        self
+    }
+}
+
+// Caminhos do documento; o operationId vem do método e do caminho
+#[derive(Default)]
+struct Paths(Map<String, Value>);
+
+impl Paths {
+    fn add(&mut self, method: &str, path: &str, operation: Operation) {
+        let words: Vec<String> = path
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+        let id = if words.is_empty() {
+            format!("{}_root", method)
+        } else {
+            format!("{}_{}", method, words.join("_"))
+        };
+        
+        let mut operation = operation.0;
+        operation["operationId"] = json!(id);
+        self.0
+            .entry(path.to_string())
+            .or_insert_with(|| json!({}))[method] = operation;
+    }
+}
+
+fn document(config: &Config, server: &str) -> Value {
+    let mut paths = Paths::default();
+    
+    http_methods(&mut paths);
+    inspection(&mut paths);
+    dynamic(&mut paths);
+    cookies(&mut paths);
+    auth(&mut paths, config);
+    redirects(&mut paths);
+    formats(&mut paths);
+    protocol(&mut paths);
+    monitoring(&mut paths);
+    if config.mocks.enabled {
+        mocks(&mut paths);
+    }
+    if config.proxy.mode != ProxyMode::Off {
+        recordings(&mut paths);
+    }
+    
+    let mut description = format!(
+        "Generated from the running configuration. gRPC (rustjin.echo.v1.EchoService, with reflection) \
+         is served on the same port and is not described here. Request bodies are limited to {} bytes.",
+        MAX_BODY
+    );
+    if config.chaos.enabled {
+        description.push_str(
+            " Chaos is enabled: any route accepts X-RustJin-Delay, X-RustJin-Jitter, X-RustJin-Status, \
+             X-RustJin-Fail-Rate and X-RustJin-Drop-Rate, and answers 400 (Invalid chaos header) on bad values.",
+        );
+    }
+    
+    json!({
+        "openapi": "3.1.0",
+        "info": {
+            "title": "RustJin",
+            "version": env!("CARGO_PKG_VERSION"),
+            "description": description,
+            "license": { "name": "MIT", "identifier": "MIT" }
+        },
+        "servers": [{ "url": server }],
+        "paths": paths.0,
+        "components": { "schemas": schemas() }
+    })
+}
+
+fn schemas() -> Value {
+    let formats: Vec<&str> = formats::NAMES.to_vec();
+    json!({
+        "Error": {
+            "type": "object",
+            "required": ["error"],
+            "properties": {
+                "error": { "type": "string" },
+                "message": { "type": "string" }
+            },
+            "additionalProperties": true
+        },
+        "RequestInfo": {
+            "type": "object",
+            "description": "Echo of the request; secrets are shown as [REDACTED]",
+            "required": ["args", "headers", "origin", "url"],
+            "properties": {
+                "method": { "type": "string" },
+                "args": string_map(),
+                "headers": string_map(),
+                "origin": { "type": "string" },
+                "url": { "type": "string" },
+                "data": { "type": "string", "description": "Raw body; binary bodies as a base64 data URL" },
+                "json": { "description": "Body decoded from JSON or the declared format" },
+                "form": string_map(),
+                "decode_error": { "type": "string", "description": "Why the body did not decode in the declared format" }
+            }
+        },
+        "EchoFormat": { "type": "string", "enum": formats },
+        "Metrics": {
+            "type": "object",
+            "properties": {
+                "total_requests": { "type": "integer" },
+                "successful_requests": { "type": "integer" },
+                "failed_requests": { "type": "integer" },
+                "security_blocks": object(json!({
+                    "redirects_blocked": { "type": "integer" },
+                    "delays_blocked": { "type": "integer" },
+                    "bytes_blocked": { "type": "integer" },
+                    "dangerous_urls_blocked": { "type": "integer" },
+                    "dangerous_urls_by_reason": { "type": "object", "additionalProperties": { "type": "integer" } }
+                })),
+                "endpoint_stats": { "type": "object", "additionalProperties": { "type": "integer" } }
+            }
+        },
+        "Health": object(json!({
+            "status": { "type": "string", "const": "healthy" },
+            "uptime_seconds": { "type": "integer" },
+            "started_at": { "type": "string", "format": "date-time" },
+            "version": { "type": "string" },
+            "service": { "type": "string" }
+        })),
+        "Session": object(json!({
+            "authenticated": { "type": "boolean" },
+            "username": { "type": "string" },
+            "csrf_token": { "type": "string" },
+            "created_at": { "type": "string", "format": "date-time" },
+            "expires_at": { "type": "string", "format": "date-time" },
+            "rotations": { "type": "integer" }
+        })),
+        "SessionCredentials": object(json!({
+            "username": { "type": "string" },
+            "password": { "type": "string" },
+            "csrf_token": { "type": "string", "description": "Or the X-CSRF-Token header" }
+        })),
+        "Cookies": object(json!({ "cookies": string_map() })),
+        "Authenticated": {
+            "type": "object",
+            "properties": { "authenticated": { "type": "boolean", "const": true } },
+            "additionalProperties": true
+        },
+        "TlsInfo": object(json!({
+            "protocol": { "type": "string" },
+            "cipher_suite": { "type": "string" },
+            "alpn": { "type": ["string", "null"] },
+            "sni": { "type": ["string", "null"] },
+            "client_certificate": { "type": "boolean" }
+        })),
+        "ClientCertificate": object(json!({
+            "subject": { "type": "string" },
+            "issuer": { "type": "string" },
+            "sans": { "type": "array", "items": { "type": "string" } },
+            "serial": { "type": "string" },
+            "not_before": { "type": "string", "format": "date-time" },
+            "not_after": { "type": "string", "format": "date-time" },
+            "currently_valid": { "type": "boolean" },
+            "fingerprint_sha256": { "type": "string" },
+            "chain_length": { "type": "integer" },
+            "verified": { "type": "boolean" }
+        })),
+        "Protocol": {
+            "type": "object",
+            "properties": {
+                "http_version": { "type": "string" },
+                "alpn": { "type": ["string", "null"] },
+                "secure": { "type": "boolean" },
+                "h2c": { "type": ["string", "null"], "enum": ["prior-knowledge", "upgrade", null] },
+                "connection_id": { "type": "integer" },
+                "request_number": { "type": "integer" },
+                "connection_reused": { "type": "boolean" },
+                "stream_id": {
+                    "type": ["integer", "null"],
+                    "description": "QUIC stream ID over HTTP/3; 1 for the h2c Upgrade request; null on other HTTP/2 requests"
+                },
+                "stream_id_unavailable": { "type": "string", "description": "Why stream_id is null" },
+                "settings": { "type": "object" }
+            }
+        },
+        "TextMatcher": {
+            "description": "Exact text, or rules that must all hold",
+            "oneOf": [
+                { "type": "string" },
+                object(json!({
+                    "equal_to": { "type": "string" },
+                    "contains": { "type": "string" },
+                    "matches": { "type": "string", "format": "regex" },
+                    "absent": { "type": "boolean" }
+                }))
+            ]
+        },
+        "RequestPattern": {
+            "type": "object",
+            "required": ["path"],
+            "properties": {
+                "method": { "type": "string", "description": "HTTP method or ANY" },
+                "path": { "type": "string", "description": ":name captures a segment, * one segment, ** the rest" },
+                "query": { "type": "object", "additionalProperties": reference("TextMatcher") },
+                "headers": { "type": "object", "additionalProperties": reference("TextMatcher") },
+                "body": { "type": "array", "items": reference("BodyMatcher") }
+            }
+        },
+        "BodyMatcher": {
+            "type": "object",
+            "required": ["path"],
+            "properties": {
+                "path": { "type": "string", "examples": ["$.items[0].sku"] },
+                "equal_to": { "description": "Any JSON value" },
+                "contains": { "type": "string" },
+                "matches": { "type": "string", "format": "regex" },
+                "absent": { "type": "boolean" }
+            }
+        },
+        "Mapping": {
+            "type": "object",
+            "required": ["request", "response"],
+            "properties": {
+                "id": { "type": "string" },
+                "name": { "type": "string" },
+                "priority": { "type": "integer", "default": 5, "description": "Lowest wins" },
+                "request": reference("RequestPattern"),
+                "response": {
+                    "type": "object",
+                    "properties": {
+                        "status": { "type": "integer", "minimum": 200, "maximum": 599, "default": 200 },
+                        "headers": string_map(),
+                        "body": { "description": "Text is sent as text/plain, anything else as JSON; both accept {{...}} templates" },
+                        "base64_body": { "type": "string", "contentEncoding": "base64", "description": "Raw bytes, never templated" },
+                        "delay_ms": { "type": "integer", "minimum": 0, "maximum": MAX_DELAY * 1000 }
+                    }
+                },
+                "scenario": { "type": "string" },
+                "required_state": { "type": "string" },
+                "new_state": { "type": "string" }
+            }
+        },
+        "LoggedRequest": object(json!({
+            "id": { "type": "string" },
+            "logged_at": { "type": "string", "format": "date-time" },
+            "path": { "type": "string" },
+            "route": { "type": ["string", "null"] },
+            "mapping_id": { "type": ["string", "null"] },
+            "status": { "type": "integer" },
+            "body_captured": { "type": "boolean" },
+            "request": reference("RequestInfo")
+        })),
+        "LoggedRequests": object(json!({
+            "total": { "type": "integer" },
+            "requests": { "type": "array", "items": reference("LoggedRequest") }
+        })),
+        "Har": {
+            "type": "object",
+            "description": "HAR 1.2 (http://www.softwareishard.com/blog/har-12-spec/)",
+            "required": ["log"],
+            "properties": { "log": { "type": "object" } }
+        }
+    })
+}
+
+// Rotas
+
+fn echo(tag: &str, summary: &str) -> Operation {
+    let mut echo = operation(tag, summary)
+        .query(
+            "format",
+            reference("EchoFormat"),
+            "Response format; takes precedence over Accept",
+        )
+        .respond(
+            200,
+            "Echo of the request",
+            &[("application/json", reference("RequestInfo"))],
+        )
+        .error(
+            400,
+            "Unsupported format",
+            json!({ "error": "Unsupported format", "supported": formats::NAMES, "message": "Unsupported format: 'csv'" }),
+        );
+    for name in formats::NAMES.iter().skip(1) {
+        if let Some(format) = formats::Format::from_name(name) {
+            echo.0["responses"]["200"]["content"][format.content_type()] = json!({ "schema": reference("RequestInfo") });
+        }
+    }
+    echo
+}
+
+fn with_body(operation: Operation) -> Operation {
+    formats::NAMES
+        .iter()
+        .filter_map(|name| formats::Format::from_name(name))
+        .fold(operation, |operation, format| operation.body(format.content_type(), json!({})))
+        .body("application/x-www-form-urlencoded", string_map())
+        .body("application/octet-stream", binary())
+        .respond(413, "Request body too large", &[])
+}
+
+fn http_methods(paths: &mut Paths) {
+    let tag = "HTTP Methods";
+    paths.add("get", "/get", echo(tag, "Echo a GET request"));
+    paths.add("post", "/post", with_body(echo(tag, "Echo a POST request")));
+    paths.add("put", "/put", with_body(echo(tag, "Echo a PUT request")));
+    paths.add("patch", "/patch", with_body(echo(tag, "Echo a PATCH request")));
+    paths.add("delete", "/delete", echo(tag, "Echo a DELETE request"));
+    
+    for method in ANY_METHODS {
+        paths.add(
+            method,
+            "/anything",
+            with_body(echo(tag, "Echo any request, including the method")),
+        );
+        paths.add(
+            method,
+            "/anything/{path}",
+            with_body(echo(tag, "Echo any request under /anything"))
+                .path("path", string(), "Any path, including slashes"),
+        );
+    }
+}
+
+fn inspection(paths: &mut Paths) {
+    let tag = "Request Inspection";
+    paths.add(
+        "get",
+        "/headers",
+        operation(tag, "Request headers").json(object(json!({ "headers": string_map() }))),
+    );
+    paths.add(
+        "get",
+        "/ip",
+        operation(tag, "Client address (X-Forwarded-For, X-Real-IP)")
+            .json(object(json!({ "origin": string() }))),
+    );
+    paths.add(
+        "get",
+        "/user-agent",
+        operation(tag, "User-Agent header").json(object(json!({ "user-agent": string() }))),
+    );
+}
+
+fn dynamic(paths: &mut Paths) {
+    for method in ["get", "post", "put", "delete"] {
+        paths.add(
+            method,
+            "/status/{code}",
+            operation("Status Codes", "Respond with the given status")
+                .describe("Codes outside 100-999 answer 200")
+                .path("code", integer(0, Some(u16::MAX as u64)), "Status code")
+                .respond(200, "The requested status, with an empty body", &[]),
+        );
+    }
+    
+    let tag = "Dynamic Data";
+    paths.add(
+        "get",
+        "/delay/{seconds}",
+        operation(tag, "Respond after a delay")
+            .path("seconds", integer(0, Some(MAX_DELAY)), "Delay in seconds")
+            .json(object(json!({ "delay": { "type": "integer" }, "message": string() })))
+            .error(
+                400,
+                "Delay too long",
+                json!({
+                    "error": "Delay too long",
+                    "max_delay": MAX_DELAY,
+                    "requested": MAX_DELAY + 1,
+                    "message": format!("Maximum delay is {} seconds", MAX_DELAY)
+                }),
+            ),
+    );
+    paths.add(
+        "get",
+        "/bytes/{n}",
+        operation(tag, "Deterministic bytes (i % 256)")
+            .path("n", integer(0, Some(MAX_BYTES as u64)), "Number of bytes")
+            .ok("application/octet-stream", binary())
+            .error(
+                400,
+                "Too many bytes requested",
+                json!({
+                    "error": "Too many bytes requested",
+                    "max_bytes": MAX_BYTES,
+                    "requested": MAX_BYTES + 1,
+                    "message": format!("Maximum {} bytes allowed", MAX_BYTES)
+                }),
+            ),
+    );
+    paths.add(
+        "get",
+        "/stream/{n}",
+        operation(tag, "Newline-delimited JSON objects")
+            .path("n", integer(0, Some(MAX_LINES as u64)), "Number of lines")
+            .ok("application/json", string())
+            .error(
+                400,
+                "Too many lines requested",
+                json!({
+                    "error": "Too many lines requested",
+                    "max_lines": MAX_LINES,
+                    "requested": MAX_LINES + 1,
+                    "message": format!("Maximum {} lines allowed", MAX_LINES)
+                }),
+            ),
+    );
+    paths.add(
+        "get",
+        "/uuid",
+        operation(tag, "Random UUID v4").json(object(json!({ "uuid": { "type": "string", "format": "uuid" } }))),
+    );
+    paths.add(
+        "get",
+        "/base64/{value}",
+        operation(tag, "Decode base64 into UTF-8 text")
+            .path("value", string(), "Standard base64")
+            .ok("text/plain", string())
+            .respond(400, "Invalid base64 or Invalid UTF-8", &[("text/plain", string())]),
+    );
+    
+    let image_params = |operation: Operation| {
+        let size = integer(1, Some(images::MAX_DIMENSION as u64));
+        let mut size = size;
+        size["default"] = json!(images::DEFAULT_SIZE);
+        operation
+            .query("width", size.clone(), "Width in pixels")
+            .query("height", size, "Height in pixels")
+            .query("color", string(), "Background color as hex (3498db)")
+            .query(
+                "text",
+                json!({ "type": "string", "maxLength": images::MAX_TEXT_LENGTH }),
+                "Text drawn on the image",
+            )
+            .error(
+                400,
+                "Invalid image parameters",
+                json!({
+                    "error": "Invalid image parameters",
+                    "max_dimension": images::MAX_DIMENSION,
+                    "message": format!("width must be between 1 and {}", images::MAX_DIMENSION)
+                }),
+            )
+            .error(
+                500,
+                "Image encoding failed",
+                json!({ "error": "Image encoding failed", "message": "..." }),
+            )
+    };
+    let image_content: Vec<(&str, Value)> = images::MEDIA_TYPES.iter().map(|media_type| (*media_type, binary())).collect();
+    paths.add(
+        "get",
+        "/image",
+        image_params(operation("Images", "Generated image in the format chosen by Accept"))
+            .respond(200, "OK", &image_content)
+            .error(
+                406,
+                "Not Acceptable",
+                json!({
+                    "error": "Not Acceptable",
+                    "accept": images::MEDIA_TYPES,
+                    "message": "Client did not request a supported media type"
+                }),
+            ),
+    );
+    paths.add(
+        "get",
+        "/image/{format}",
+        image_params(operation("Images", "Generated image in the given format"))
+            .path(
+                "format",
+                json!({ "type": "string", "enum": ["png", "jpeg", "jpg", "webp", "gif", "svg"] }),
+                "Image format",
+            )
+            .respond(200, "OK", &image_content)
+            .error(
+                404,
+                "Unsupported image format",
+                json!({
+                    "error": "Unsupported image format",
+                    "supported": ["png", "jpeg", "webp", "gif", "svg"],
+                    "requested": "bmp"
+                }),
+            ),
+    );
+    paths.add(
+        "get",
+        "/logo.png",
+        operation("Images", "RustJin logo").ok("image/png", binary()),
+    );
+}
+
+fn cookies(paths: &mut Paths) {
+    let tag = "Cookies";
+    let invalid = json!({ "error": "Invalid cookie", "message": "Invalid SameSite attribute: 'x' (expected Strict, Lax or None)" });
+    let attributes = |operation: Operation| {
+        cookies::ATTRIBUTE_PARAMS.iter().fold(operation, |operation, name| {
+            let schema = match *name {
+                "max_age" => json!({ "type": "integer" }),
+                "samesite" => json!({ "type": "string", "enum": ["Strict", "Lax", "None"] }),
+                "path" | "domain" | "expires" => string(),
+                _ => boolean(),
+            };
+            operation.query(name, schema, "Set-Cookie attribute (redirect=true answers 302 to /cookies)")
+        })
+    };
+    
+    paths.add(
+        "get",
+        "/cookies",
+        operation(tag, "Cookies sent by the client").json(reference("Cookies")),
+    );
+    paths.add(
+        "get",
+        "/cookies/set",
+        attributes(operation(tag, "Set cookies from the query string"))
+            .describe("Every query parameter that is not an attribute becomes a cookie: ?name=value")
+            .json(object(json!({
+                "cookies": string_map(),
+                "set_cookie": { "type": "array", "items": string() }
+            })))
+            .respond(302, "Redirect to /cookies (redirect=true)", &[])
+            .error(400, "Invalid cookie", invalid.clone()),
+    );
+    paths.add(
+        "get",
+        "/cookies/delete",
+        attributes(operation(tag, "Expire cookies"))
+            .describe("Names come from ?name=a&name=b or httpbin style ?a&b")
+            .json(object(json!({
+                "message": string(),
+                "deleted": { "type": "array", "items": string() }
+            })))
+            .respond(302, "Redirect to /cookies (redirect=true)", &[])
+            .error(400, "Invalid cookie", invalid),
+    );
+}
+
+fn auth(paths: &mut Paths, config: &Config) {
+    let tag = "Auth";
+    let api_keys = &config.api_keys;
+    
+    paths.add(
+        "get",
+        "/basic-auth/{user}/{password}",
+        operation(tag, "HTTP Basic authentication")
+            .path("user", string(), "Expected user")
+            .path("password", string(), "Expected password")
+            .header("Authorization", string(), "Basic base64(user:password)")
+            .json(reference("Authenticated"))
+            .respond(401, "Missing or wrong credentials", &[]),
+    );
+    paths.add(
+        "get",
+        "/bearer",
+        operation(tag, "Bearer token authentication")
+            .header("Authorization", string(), "Bearer <token>")
+            .json(reference("Authenticated"))
+            .respond(401, "Missing bearer token", &[]),
+    );
+    
+    let api_key = |operation: Operation| {
+        operation
+            .describe("The key is looked up in the header, then the query string, then the cookie")
+            .header(&api_keys.header_name, string(), "API key")
+            .query(&api_keys.query_param, string(), "API key")
+            .parameter("cookie", &api_keys.cookie_name, string(), "API key")
+            .json(reference("Authenticated"))
+            .error(
+                401,
+                "Missing API key",
+                json!({
+                    "error": "Missing API key",
+                    "header": api_keys.header_name,
+                    "query_param": api_keys.query_param,
+                    "cookie": api_keys.cookie_name,
+                    "message": "Provide an API key via header, query parameter or cookie"
+                }),
+            )
+            .error(
+                401,
+                "Invalid API key",
+                json!({
+                    "error": "Invalid API key",
+                    "source": "header",
+                    "message": "The API key provided is not recognized"
+                }),
+            )
+    };
+    paths.add("get", "/api-key", api_key(operation(tag, "API key authentication")));
+    paths.add(
+        "get",
+        "/api-key/{scope}",
+        api_key(operation(tag, "API key authentication with a required scope"))
+            .path("scope", string(), "Scope the key must grant")
+            .error(
+                403,
+                "Insufficient scope",
+                json!({
+                    "error": "Insufficient scope",
+                    "required_scope": "write",
+                    "granted_scopes": ["read"],
+                    "message": "API key does not grant the 'write' scope"
+                }),
+            ),
+    );
+    
+    let tag = "Session";
+    let not_authenticated = json!({
+        "error": "Not authenticated",
+        "message": "Session cookie is missing, invalid or expired"
+    });
+    let invalid_csrf = json!({
+        "error": "Invalid CSRF token",
+        "message": "Send the session csrf_token in the X-CSRF-Token header or csrf_token field"
+    });
+    let change = |operation: Operation| {
+        operation
+            .header("X-CSRF-Token", string(), "Session CSRF token (or the csrf_token form field)")
+            .body("application/x-www-form-urlencoded", reference("SessionCredentials"))
+            .body("application/json", reference("SessionCredentials"))
+            .error(401, "Not authenticated", not_authenticated.clone())
+            .error(403, "Invalid CSRF token", invalid_csrf.clone())
+    };
+    
+    paths.add(
+        "get",
+        "/session/login",
+        operation(tag, "Login form")
+            .describe("Sets the rustjin_csrf cookie used by POST /session/login")
+            .ok("text/html", string()),
+    );
+    paths.add(
+        "post",
+        "/session/login",
+        operation(tag, "Start a session")
+            .describe("Forms are answered with 303 to /session/me; JSON bodies get the session")
+            .body("application/x-www-form-urlencoded", reference("SessionCredentials"))
+            .body("application/json", reference("SessionCredentials"))
+            .header("X-CSRF-Token", string(), "CSRF token from GET /session/login")
+            .json(reference("Session"))
+            .respond(303, "Form login: redirect to /session/me", &[])
+            .error(
+                401,
+                "Invalid credentials",
+                json!({ "error": "Invalid credentials", "message": "Username or password is incorrect" }),
+            )
+            .error(
+                403,
+                "Invalid CSRF token",
+                json!({
+                    "error": "Invalid CSRF token",
+                    "message": "GET /session/login first and submit its csrf_token with the credentials"
+                }),
+            ),
+    );
+    paths.add(
+        "get",
+        "/session/me",
+        operation(tag, "Current session")
+            .json(reference("Session"))
+            .error(401, "Not authenticated", not_authenticated.clone()),
+    );
+    paths.add(
+        "post",
+        "/session/rotate",
+        change(operation(tag, "Issue a new session id")).json(reference("Session")),
+    );
+    paths.add(
+        "post",
+        "/session/logout",
+        change(operation(tag, "End the session"))
+            .json(object(json!({ "authenticated": { "type": "boolean" }, "message": string() })))
+            .respond(303, "Form logout: redirect to /session/login", &[]),
+    );
+}
+
+fn redirects(paths: &mut Paths) {
+    let tag = "Redirects";
+    let status_code = json!({ "type": "integer", "enum": REDIRECT_STATUS_CODES, "default": 302 });
+    let invalid_status = json!({
+        "error": "Invalid redirect status",
+        "allowed": REDIRECT_STATUS_CODES,
+        "requested": 200,
+        "message": "Redirect status must be one of 301, 302, 303, 307 or 308"
+    });
+    let chain = |summary: &str| {
+        operation(tag, summary)
+            .describe("Accepts any method; 307 and 308 end at /anything, the others at /get")
+            .path("n", integer(0, Some(MAX_REDIRECTS as u64)), "Remaining hops")
+            .query("status_code", status_code.clone(), "Redirect status")
+            .query("hops", integer(0, None), "Hops taken so far (set by the chain)")
+            .respond(302, "Redirect; X-Redirect-Hops counts the hops", &[])
+            .error(
+                400,
+                "Too many redirects",
+                json!({
+                    "error": "Too many redirects",
+                    "max_allowed": MAX_REDIRECTS,
+                    "requested": MAX_REDIRECTS + 1,
+                    "message": format!("Maximum {} redirects allowed", MAX_REDIRECTS)
+                }),
+            )
+            .error(400, "Invalid redirect status", invalid_status.clone())
+    };
+    
+    for method in ANY_METHODS {
+        paths.add(
+            method,
+            "/redirect/{n}",
+            chain("Redirect chain").query("absolute", boolean(), "Continue with absolute redirects"),
+        );
+        paths.add(method, "/relative-redirect/{n}", chain("Relative redirect chain"));
+        paths.add(method, "/absolute-redirect/{n}", chain("Absolute redirect chain"));
+        paths.add(
+            method,
+            "/redirect-loop",
+            operation(tag, "Redirect to itself forever")
+                .query("status_code", status_code.clone(), "Redirect status")
+                .respond(302, "Redirect to /redirect-loop", &[])
+                .error(400, "Invalid redirect status", invalid_status.clone()),
+        );
+        paths.add(
+            method,
+            "/redirect-to",
+            operation(tag, "Redirect to a URL")
+                .describe("Private and link-local targets are refused unless RUSTJIN_REDIRECT_ALLOW_PRIVATE is set")
+                .query(
+                    "url",
+                    json!({ "type": "string", "maxLength": url_policy::MAX_URL_LENGTH }),
+                    "Target URL (http or https)",
+                )
+                .required()
+                .query("status_code", status_code.clone(), "Redirect status")
+                .respond(302, "Redirect to the URL", &[])
+                .error(
+                    400,
+                    "URL too long",
+                    json!({
+                        "error": "URL too long",
+                        "reason": "too_long",
+                        "message": "URL exceeds maximum allowed length",
+                        "max_length": url_policy::MAX_URL_LENGTH
+                    }),
+                )
+                .error(
+                    400,
+                    "Invalid protocol",
+                    json!({ "error": "Invalid protocol", "reason": "scheme", "message": "Protocol not allowed for security reasons" }),
+                )
+                .error(
+                    400,
+                    "URL not allowed",
+                    json!({ "error": "URL not allowed", "reason": "private", "message": "..." }),
+                )
+                .error(400, "Invalid redirect status", invalid_status.clone()),
+        );
+    }
+}
+
+fn formats(paths: &mut Paths) {
+    let tag = "Response Formats";
+    paths.add(
+        "get",
+        "/json",
+        operation(tag, "Sample JSON document").json(json!({ "type": "object" })),
+    );
+    paths.add("get", "/html", operation(tag, "Sample HTML page").ok("text/html", string()));
+    paths.add("get", "/xml", operation(tag, "Sample XML document").ok("application/xml", string()));
+    paths.add(
+        "get",
+        "/encoding/utf8",
+        operation(tag, "UTF-8 sample page").ok("text/html", string()),
+    );
+    paths.add("get", "/robots.txt", operation(tag, "robots.txt").ok("text/plain", string()));
+    paths.add("get", "/deny", operation(tag, "Page denied by robots.txt").ok("text/plain", string()));
+    
+    let content: Vec<(&str, Value)> = negotiate::MEDIA_TYPES.iter().map(|media_type| (*media_type, string())).collect();
+    paths.add(
+        "get",
+        "/negotiate",
+        operation(tag, "Content negotiation")
+            .describe(format!(
+                "Picks a representation from Accept, Accept-Language ({}) and Accept-Charset ({})",
+                negotiate::LANGUAGES.join(", "),
+                negotiate::CHARSETS.join(", ")
+            ))
+            .header("Accept", string(), "Preferred media types")
+            .header("Accept-Language", string(), "Preferred languages")
+            .header("Accept-Charset", string(), "Preferred charsets")
+            .respond(200, "OK", &content)
+            .error(
+                406,
+                "Not Acceptable",
+                json!({
+                    "error": "Not Acceptable",
+                    "available": {
+                        "media_types": negotiate::MEDIA_TYPES,
+                        "languages": negotiate::LANGUAGES,
+                        "charsets": negotiate::CHARSETS
+                    },
+                    "message": "No available representation matches the Accept, Accept-Language and Accept-Charset headers"
+                }),
+            ),
+    );
+}
+
+fn protocol(paths: &mut Paths) {
+    let invalid_graphql = json!({ "error": "Invalid GraphQL request", "message": "..." });
+    paths.add(
+        "get",
+        "/graphql",
+        operation("GraphQL", "GraphiQL, GET queries and WebSocket subscriptions")
+            .describe("Without query or extensions the browser gets GraphiQL; WebSocket upgrades speak graphql-transport-ws and graphql-ws")
+            .query("query", string(), "GraphQL document")
+            .query("variables", string(), "JSON object")
+            .query("operationName", string(), "Operation to run")
+            .query("extensions", string(), "JSON object (persisted queries)")
+            .respond(
+                200,
+                "GraphQL response or GraphiQL",
+                &[("application/json", json!({ "type": "object" })), ("text/html", string())],
+            )
+            .error(400, "Invalid GraphQL request", invalid_graphql.clone()),
+    );
+    paths.add(
+        "post",
+        "/graphql",
+        operation("GraphQL", "Run a GraphQL operation or a batch")
+            .body(
+                "application/json",
+                json!({
+                    "oneOf": [
+                        object(json!({
+                            "query": string(),
+                            "variables": { "type": "object" },
+                            "operationName": string(),
+                            "extensions": { "type": "object" }
+                        })),
+                        { "type": "array", "items": { "type": "object" } }
+                    ]
+                }),
+            )
+            .json(json!({ "type": ["object", "array"] }))
+            .error(400, "Invalid GraphQL request", invalid_graphql),
+    );
+    
+    let tag = "Protocol";
+    paths.add(
+        "get",
+        "/protocol",
+        operation(tag, "HTTP version, stream and connection reuse").json(reference("Protocol")),
+    );
+    paths.add(
+        "get",
+        "/tls/info",
+        operation(tag, "Negotiated TLS parameters")
+            .json(reference("TlsInfo"))
+            .error(
+                400,
+                "Not a TLS connection",
+                json!({ "error": "Not a TLS connection", "message": "This request was received over plain HTTP", "tls_port": 8443 }),
+            ),
+    );
+    paths.add(
+        "get",
+        "/tls/ca.pem",
+        operation(tag, "Generated CA certificate")
+            .ok("application/x-pem-file", string())
+            .error(
+                404,
+                "Not Found",
+                json!({ "error": "Not Found", "message": "No generated CA: TLS is disabled or uses a supplied certificate" }),
+            ),
+    );
+    paths.add(
+        "get",
+        "/tls/client-cert",
+        operation(tag, "Client certificate presented over mTLS")
+            .json(reference("ClientCertificate"))
+            .error(
+                403,
+                "Forbidden",
+                json!({ "error": "Forbidden", "message": "No client certificate was presented", "client_auth": "request" }),
+            )
+            .error(
+                400,
+                "Invalid client certificate",
+                json!({ "error": "Invalid client certificate", "message": "..." }),
+            ),
+    );
+    paths.add(
+        "get",
+        "/fault",
+        operation("Faults", "Available connection faults").json(object(json!({
+            "faults": { "type": "array", "items": object(json!({ "path": string(), "description": string() })) },
+            "note": string()
+        }))),
+    );
+    paths.add(
+        "get",
+        "/fault/{name}",
+        operation("Faults", "Break the connection")
+            .describe("Applies to the first HTTP/1.x request of a connection; there is no HTTP response")
+            .path("name", string(), "Fault name from GET /fault")
+            .error(
+                400,
+                "Fault not injected",
+                json!({
+                    "error": "Fault not injected",
+                    "message": "Faults apply only to the first HTTP/1.x request of a connection; open a new HTTP/1.1 connection"
+                }),
+            )
+            .error(404, "Unknown fault", json!({ "error": "Unknown fault", "available": ["reset", "reset-after-headers", "hang"] })),
+    );
+}
+
+fn monitoring(paths: &mut Paths) {
+    let tag = "Monitoring";
+    paths.add("get", "/metrics", operation(tag, "Request metrics").json(reference("Metrics")));
+    paths.add("get", "/health", operation(tag, "Health check").json(reference("Health")));
+    paths.add("get", "/", operation(tag, "Home page").ok("text/html", string()));
+    paths.add(
+        "get",
+        "/openapi.json",
+        operation(tag, "This document").json(json!({ "type": "object" })),
+    );
+    paths.add("get", "/docs", operation(tag, "Interactive API docs").ok("text/html", string()));
+}
+
+fn mocks(paths: &mut Paths) {
+    let tag = "Mocks";
+    let invalid_mapping = json!({ "error": "Invalid mapping", "message": "request.path must start with '/'" });
+    let mapping_not_found = json!({ "error": "Mapping not found", "id": "..." });
+    let scenario_not_found = json!({ "error": "Scenario not found", "name": "..." });
+    let mapping_list = object(json!({
+        "total": { "type": "integer" },
+        "mappings": { "type": "array", "items": reference("Mapping") }
+    }));
+    
+    paths.add("get", "/__admin/mappings", operation(tag, "Active mappings").json(mapping_list));
+    paths.add(
+        "post",
+        "/__admin/mappings",
+        operation(tag, "Register a mapping (same id replaces)")
+            .body("application/json", reference("Mapping"))
+            .respond(201, "Created", &[("application/json", reference("Mapping"))])
+            .error(400, "Invalid mapping", invalid_mapping),
+    );
+    paths.add(
+        "delete",
+        "/__admin/mappings",
+        operation(tag, "Remove every mapping").json(object(json!({ "deleted": { "type": "integer" } }))),
+    );
+    paths.add(
+        "post",
+        "/__admin/mappings/reset",
+        operation(tag, "Back to the mappings loaded at startup")
+            .json(object(json!({ "total": { "type": "integer" } }))),
+    );
+    paths.add(
+        "get",
+        "/__admin/mappings/{id}",
+        operation(tag, "One mapping")
+            .path("id", string(), "Mapping id")
+            .json(reference("Mapping"))
+            .error(404, "Mapping not found", mapping_not_found.clone()),
+    );
+    paths.add(
+        "delete",
+        "/__admin/mappings/{id}",
+        operation(tag, "Remove a mapping")
+            .path("id", string(), "Mapping id")
+            .json(object(json!({ "deleted": reference("Mapping") })))
+            .error(404, "Mapping not found", mapping_not_found),
+    );
+    
+    paths.add(
+        "get",
+        "/__admin/scenarios",
+        operation(tag, "Scenarios and their current state").json(object(json!({
+            "scenarios": {
+                "type": "array",
+                "items": object(json!({
+                    "name": string(),
+                    "state": string(),
+                    "possible_states": { "type": "array", "items": string() }
+                }))
+            }
+        }))),
+    );
+    paths.add(
+        "post",
+        "/__admin/scenarios/reset",
+        operation(tag, "Every scenario back to Started").json(object(json!({ "state": string() }))),
+    );
+    paths.add(
+        "put",
+        "/__admin/scenarios/{name}/state",
+        operation(tag, "Move a scenario to a state")
+            .path("name", string(), "Scenario name")
+            .body("application/json", object(json!({ "state": string() })))
+            .json(object(json!({ "name": string(), "state": string() })))
+            .error(
+                400,
+                "Unknown scenario state",
+                json!({ "error": "Unknown scenario state", "state": "...", "possible_states": ["Started"] }),
+            )
+            .error(400, "Invalid request body", json!({ "error": "Invalid request body", "message": "..." }))
+            .error(404, "Scenario not found", scenario_not_found.clone()),
+    );
+    paths.add(
+        "post",
+        "/__admin/scenarios/{name}/reset",
+        operation(tag, "A scenario back to Started")
+            .path("name", string(), "Scenario name")
+            .json(object(json!({ "name": string(), "state": string() })))
+            .error(404, "Scenario not found", scenario_not_found),
+    );
+    
+    let tag = "Journal";
+    let limit = |operation: Operation| operation.query("limit", integer(0, None), "Newest entries to return");
+    let criteria = |operation: Operation| {
+        operation
+            .describe("Same format as a mapping's request; without path, any path matches")
+            .body("application/json", reference("RequestPattern"))
+            .error(
+                400,
+                "Invalid request pattern",
+                json!({ "error": "Invalid request pattern", "message": "..." }),
+            )
+    };
+    paths.add(
+        "get",
+        "/__admin/requests",
+        limit(operation(tag, "Received requests, newest first")).json(reference("LoggedRequests")),
+    );
+    paths.add(
+        "delete",
+        "/__admin/requests",
+        operation(tag, "Clear the journal").json(object(json!({ "deleted": { "type": "integer" } }))),
+    );
+    paths.add(
+        "post",
+        "/__admin/requests/count",
+        criteria(operation(tag, "Count matching requests"))
+            .json(object(json!({ "count": { "type": "integer" } }))),
+    );
+    paths.add(
+        "post",
+        "/__admin/requests/find",
+        criteria(operation(tag, "Find matching requests")).json(reference("LoggedRequests")),
+    );
+    paths.add(
+        "get",
+        "/__admin/requests/unmatched",
+        operation(tag, "Requests no route or mapping answered").json(reference("LoggedRequests")),
+    );
+    paths.add(
+        "get",
+        "/__admin/requests/har",
+        limit(operation(tag, "Captured traffic as HAR 1.2, oldest first")).json(reference("Har")),
+    );
+}
+
+fn recordings(paths: &mut Paths) {
+    let tag = "Recordings";
+    paths.add(
+        "get",
+        "/__admin/recordings",
+        operation(tag, "Proxy mode and loaded recordings").json(object(json!({
+            "mode": { "type": "string", "enum": ["record", "replay"] },
+            "upstream": { "type": ["string", "null"] },
+            "directory": string(),
+            "total": { "type": "integer" },
+            "recordings": {
+                "type": "array",
+                "items": object(json!({
+                    "id": string(),
+                    "method": string(),
+                    "path": string(),
+                    "status": { "type": "integer" },
+                    "replayed": { "type": "integer" }
+                }))
+            }
+        }))),
+    );
+    paths.add(
+        "post",
+        "/__admin/recordings/reload",
+        operation(tag, "Reread the recordings directory")
+            .json(object(json!({ "total": { "type": "integer" } })))
+            .error(400, "Invalid recording", json!({ "error": "Invalid recording", "message": "..." }))
+            .error(409, "Not in replay mode", json!({ "error": "Not in replay mode", "mode": "record" })),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+    use std::collections::BTreeSet;
+
+    // Arquivos que registram rotas com .route(); o gRPC usa route_service e fica de fora
+    const SOURCES: [&str; 9] = [
+        include_str!("main.rs"),
+        include_str!("fault.rs"),
+        include_str!("graphql.rs"),
+        include_str!("journal.rs"),
+        include_str!("mock.rs"),
+        include_str!("openapi.rs"),
+        include_str!("protocol.rs"),
+        include_str!("proxy.rs"),
+        include_str!("tls.rs"),
+    ];
+
+    // (método, caminho no formato OpenAPI) de cada .route() do código
+    fn declared_routes() -> BTreeSet<(String, String)> {
+        let route = Regex::new(r#"\.route\(\s*"([^"]+)""#).unwrap();
+        let method = Regex::new(r"\b(get|post|put|patch|delete|any)\(").unwrap();
+        let param = Regex::new(r"[:*]([A-Za-z_]+)").unwrap();
+        let next = Regex::new(r"\n\s*[.}]").unwrap();
+        
+        let mut routes = BTreeSet::new();
+        for source in SOURCES {
+            for found in route.captures_iter(source) {
+                let rest = &source[found.get(0).unwrap().end()..];
+                let handlers = &rest[..next.find(rest).map(|m| m.start()).unwrap_or(rest.len())];
+                let path = param.replace_all(&found[1], "{$1}").to_string();
+                for name in method.captures_iter(handlers) {
+                    let methods = match &name[1] {
+                        "any" => ANY_METHODS.to_vec(),
+                        other => vec![other],
+                    };
+                    routes.extend(methods.into_iter().map(|m| (m.to_string(), path.clone())));
+                }
+            }
+        }
+        routes
+    }
+
+    fn documented_routes(config: &Config) -> BTreeSet<(String, String)> {
+        let document = document(config, "http://localhost:8105");
+        document["paths"]
+            .as_object()
+            .unwrap()
+            .iter()
+            .flat_map(|(path, item)| {
+                item.as_object()
+                    .unwrap()
+                    .keys()
+                    .map(move |method| (method.clone(), path.clone()))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn every_route_is_documented() {
+        // Com mocks e proxy ativos, todas as rotas /__admin entram no roteador e no documento
+        let mut config = Config::from_env();
+        config.mocks.enabled = true;
+        config.proxy.mode = ProxyMode::Replay;
+        
+        let declared = declared_routes();
+        let documented = documented_routes(&config);
+        assert!(declared.len() > 50, "poucas rotas encontradas: {:?}", declared);
+        assert_eq!(
+            declared.difference(&documented).collect::<Vec<_>>(),
+            Vec::<&(String, String)>::new(),
+            "rotas sem documentação"
+        );
+        assert_eq!(
+            documented.difference(&declared).collect::<Vec<_>>(),
+            Vec::<&(String, String)>::new(),
+            "documentação sem rota"
+        );
+    }
+
+    #[test]
+    fn admin_routes_follow_configuration() {
+        let mut config = Config::from_env();
+        config.mocks.enabled = false;
+        config.proxy.mode = ProxyMode::Off;
+        
+        assert!(documented_routes(&config)
+            .iter()
+            .all(|(_, path)| !path.starts_with(crate::mock::ADMIN_PREFIX)));
+    }
+}
@@ -0,0 +1,611 @@
+use axum::{
+    body::Body,
+    extract::Extension,
+    http::{
+        header::{CONNECTION, CONTENT_LENGTH, HOST, TRANSFER_ENCODING, UPGRADE},
+        Request, StatusCode, Version,
+    },
+    response::{IntoResponse, Json, Response},
+    routing::get,
+    Router,
+};
+use hyper::body::Incoming;
+use hyper_util::{
+    rt::{TokioExecutor, TokioIo},
+    server::conn::auto,
+};
+use serde::Serialize;
+use serde_json::json;
+use std::{
+    fmt,
+    future::Future,
+    io,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, ReadBuf},
+    net::TcpListener,
+};
+use tower::Service;
+
+use crate::chaos::DropConnection;
+use crate::config::{env_flag, env_or};
+use crate::fault::{self, Fault, RawStream};
+use crate::tls::TlsInfo;
+use crate::AppState;
+
+// Limites do protocolo para os SETTINGS (RFC 9113, seção 6.5.2)
+const MIN_FRAME_SIZE: u32 = 16_384;
+const MAX_FRAME_SIZE: u32 = 16_777_215;
+const MAX_WINDOW_SIZE: u32 = 2_147_483_647;
+
+// Prefácio que todo cliente HTTP/2 envia antes do primeiro frame
+const CONNECTION_PREFACE_LEN: usize = 24;
+const FRAME_HEADER_LEN: usize = 9;
+
+// Limite da primeira linha lida antes de entregar a conexão ao hyper
+const MAX_FIRST_LINE: usize = 8 * 1024;
+
+// Conexão ociosa que não envia a primeira linha nesse prazo é fechada
+const FIRST_LINE_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Headers específicos da conexão HTTP/1.1, proibidos em HTTP/2
+pub const HOP_BY_HOP_HEADERS: [&str; 8] = [
+    "connection",
+    "upgrade",
+    "http2-settings",
+    "host",
+    "keep-alive",
+    "proxy-connection",
+    "transfer-encoding",
+    "te",
+];
+
+// SETTINGS anunciados aos clientes HTTP/2, para testar controle de fluxo
+#[derive(Clone, Serialize)]
+pub struct Http2Config {
+    pub max_concurrent_streams: u32,
+    pub initial_stream_window_size: u32,
+    pub initial_connection_window_size: u32,
+    pub max_frame_size: u32,
+    pub adaptive_window: bool,
+}
+
+impl Http2Config {
+    pub fn from_env() -> Self {
+        let number = |name: &str, default: u32| env_or(name, &default.to_string()).parse().unwrap_or(default);
+        
+        Self {
+            max_concurrent_streams: number("RUSTJIN_H2_MAX_CONCURRENT_STREAMS", 200),
+            initial_stream_window_size: number("RUSTJIN_H2_INITIAL_STREAM_WINDOW", 1_048_576)
+                .min(MAX_WINDOW_SIZE),
+            initial_connection_window_size: number("RUSTJIN_H2_INITIAL_CONNECTION_WINDOW", 1_048_576)
+                .min(MAX_WINDOW_SIZE),
+            max_frame_size: number("RUSTJIN_H2_MAX_FRAME_SIZE", MIN_FRAME_SIZE)
+                .clamp(MIN_FRAME_SIZE, MAX_FRAME_SIZE),
+            adaptive_window: env_flag("RUSTJIN_H2_ADAPTIVE_WINDOW"),
+        }
+    }
+
+    fn builder(&self) -> auto::Builder<TokioExecutor> {
+        let mut builder = auto::Builder::new(TokioExecutor::new());
+        builder
+            .http2()
+            .max_concurrent_streams(self.max_concurrent_streams)
+            .initial_stream_window_size(self.initial_stream_window_size)
+            .initial_connection_window_size(self.initial_connection_window_size)
+            .max_frame_size(self.max_frame_size)
+            .adaptive_window(self.adaptive_window);
+        builder
+    }
+}
+
+// Como uma conexão em texto puro chegou ao HTTP/2
+#[derive(Clone, Copy, PartialEq)]
+enum H2c {
+    No,
+    PriorKnowledge,
+    Upgrade,
+}
+
+// Dados da conexão compartilhados por todas as requisições que passam por ela
+struct Connection {
+    id: u64,
+    requests: AtomicU64,
+    tls: Option<TlsInfo>,
+    // Número da requisição do Upgrade: h2c, que segue na conexão HTTP/2 como stream 1
+    upgrade_request: Option<u64>,
+}
+
+// Anexado a cada requisição para o /protocol
+#[derive(Clone)]
+pub struct ConnectionRequest {
+    connection_id: u64,
+    request_number: u64,
+    secure: bool,
+    h2c: H2c,
+    stream_id: Option<u64>,
+}
+
+impl ConnectionRequest {
+    // Requisição recebida por HTTP/3, onde o stream ID do QUIC é conhecido
+    pub fn quic(connection_id: u64, request_number: u64, stream_id: u64) -> Self {
+        Self {
+            connection_id,
+            request_number,
+            secure: true,
+            h2c: H2c::No,
+            stream_id: Some(stream_id),
+        }
+    }
+}
+
+static NEXT_CONNECTION_ID: AtomicU64 = AtomicU64::new(1);
+
+pub fn next_connection_id() -> u64 {
+    NEXT_CONNECTION_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+// Erro devolvido ao hyper para derrubar a conexão (HTTP/1.x) ou o stream (HTTP/2)
+#[derive(Debug)]
+struct ConnectionDropped;
+
+impl fmt::Display for ConnectionDropped {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("conexão derrubada pela camada de caos")
+    }
+}
+
+impl std::error::Error for ConnectionDropped {}
+
+type ResponseFuture = Pin<Box<dyn Future<Output = Result<Response, ConnectionDropped>> + Send>>;
+
+// Entrega as requisições de uma conexão ao Router, anotando os dados da conexão
+#[derive(Clone)]
+struct ConnectionService {
+    app: Router,
+    http2: Http2Config,
+    connection: Arc<Connection>,
+}
+
+impl ConnectionService {
+    fn call(self, mut request: Request<Incoming>) -> ResponseFuture {
+        if self.connection.upgrade_request.is_none() && self.connection.tls.is_none() && wants_h2c_upgrade(&request) {
+            if let Some(response) = self.clone().upgrade_h2c(&mut request) {
+                return Box::pin(async move { Ok(response) });
+            }
+        }
+        
+        let request_number = self.connection.requests.fetch_add(1, Ordering::Relaxed) + 1;
+        let h2c = match (request.version(), self.connection.upgrade_request.is_some()) {
+            (_, true) => H2c::Upgrade,
+            (Version::HTTP_2, false) if self.connection.tls.is_none() => H2c::PriorKnowledge,
+            _ => H2c::No,
+        };
+        
+        request.extensions_mut().insert(ConnectionRequest {
+            connection_id: self.connection.id,
+            request_number,
+            secure: self.connection.tls.is_some(),
+            h2c,
+            stream_id: (self.connection.upgrade_request == Some(request_number)).then_some(1),
+        });
+        if let Some(tls) = &self.connection.tls {
+            request.extensions_mut().insert(tls.clone());
+        }
+        
+        // Router está sempre pronto: poll_ready não precisa ser aguardado
+        let mut app = self.app;
+        let response = app.call(request.map(Body::new));
+        Box::pin(async move {
+            let Ok(response) = response.await;
+            if response.extensions().get::<DropConnection>().is_some() {
+                return Err(ConnectionDropped);
+            }
+            Ok(response)
+        })
+    }
+
+    // Responde 101 e continua a conexão em HTTP/2; a requisição original vira o stream 1
+    fn upgrade_h2c(self, request: &mut Request<Incoming>) -> Option<Response> {
+        let headers = headers_frame(request)?;
+        let on_upgrade = hyper::upgrade::on(&mut *request);
+        
+        tokio::spawn(async move {
+            let upgraded = match on_upgrade.await {
+                Ok(upgraded) => upgraded,
+                Err(e) => {
+                    tracing::debug!("⬆️  Upgrade h2c falhou: {}", e);
+                    return;
+                }
+            };
+            
+            // A requisição original não foi contada; ela é a primeira que o HTTP/2 entrega
+            let requests = self.connection.requests.load(Ordering::Relaxed);
+            let connection = Arc::new(Connection {
+                id: self.connection.id,
+                requests: AtomicU64::new(requests),
+                tls: None,
+                upgrade_request: Some(requests + 1),
+            });
+            let io = H2cUpgrade::new(TokioIo::new(upgraded), headers);
+            serve_io(TokioIo::new(io), self.app, self.http2, connection, true).await;
+        });
+        
+        Response::builder()
+            .status(StatusCode::SWITCHING_PROTOCOLS)
+            .header(CONNECTION, "Upgrade")
+            .header(UPGRADE, "h2c")
+            .body(Body::empty())
+            .ok()
+    }
+}
+
+// Upgrade: h2c só vale para HTTP/1.1 com HTTP2-Settings e sem corpo (RFC 7540, seção 3.2)
+fn wants_h2c_upgrade(request: &Request<Incoming>) -> bool {
+    let has_token = |name, token: &str| {
+        request.headers().get_all(name).iter().any(|value| {
+            value
+                .to_str()
+                .unwrap_or("")
+                .split(',')
+                .any(|item| item.trim().eq_ignore_ascii_case(token))
+        })
+    };
+    let has_body = request.headers().contains_key(TRANSFER_ENCODING)
+        || request
+            .headers()
+            .get(CONTENT_LENGTH)
+            .is_some_and(|length| length.as_bytes() != b"0");
+    
+    request.version() == Version::HTTP_11
+        && has_token(UPGRADE, "h2c")
+        && request.headers().contains_key("http2-settings")
+        && !has_body
+}
+
+// Frame HEADERS (stream 1, END_STREAM | END_HEADERS) com a requisição original em HPACK
+// literal, sem tabela de compressão
+fn headers_frame(request: &Request<Incoming>) -> Option<Vec<u8>> {
+    let authority = request
+        .uri()
+        .authority()
+        .map(|a| a.as_str().to_string())
+        .or_else(|| request.headers().get(HOST)?.to_str().ok().map(str::to_string))?;
+    let path = request.uri().path_and_query().map(|p| p.as_str()).unwrap_or("/");
+    
+    let mut block = Vec::new();
+    let pseudo = [
+        (":method", request.method().as_str()),
+        (":scheme", "http"),
+        (":authority", authority.as_str()),
+        (":path", path),
+    ];
+    for (name, value) in pseudo {
+        hpack_literal(&mut block, name.as_bytes(), value.as_bytes());
+    }
+    for (name, value) in request.headers() {
+        if !HOP_BY_HOP_HEADERS.contains(&name.as_str()) {
+            hpack_literal(&mut block, name.as_str().as_bytes(), value.as_bytes());
+        }
+    }
+    
+    // Precisa caber em um único frame do tamanho padrão
+    if block.len() > MIN_FRAME_SIZE as usize {
+        return None;
+    }
+    
+    let mut frame = Vec::with_capacity(FRAME_HEADER_LEN + block.len());
+    frame.extend_from_slice(&(block.len() as u32).to_be_bytes()[1..]);
+    frame.push(0x1);
+    frame.push(0x1 | 0x4);
+    frame.extend_from_slice(&1u32.to_be_bytes());
+    frame.extend_from_slice(&block);
+    Some(frame)
+}
+
+// "Literal Header Field without Indexing — New Name" (RFC 7541, seção 6.2.2)
+fn hpack_literal(block: &mut Vec<u8>, name: &[u8], value: &[u8]) {
+    block.push(0x00);
+    for text in [name, value] {
+        hpack_integer(block, text.len(), 7);
+        block.extend_from_slice(text);
+    }
+}
+
+fn hpack_integer(block: &mut Vec<u8>, mut value: usize, prefix_bits: u8) {
+    let max_prefix = (1usize << prefix_bits) - 1;
+    if value < max_prefix {
+        block.push(value as u8);
+        return;
+    }
+    
+    block.push(max_prefix as u8);
+    value -= max_prefix;
+    while value >= 128 {
+        block.push((value % 128) as u8 | 0x80);
+        value /= 128;
+    }
+    block.push(value as u8);
+}
+
+// Depois do 101 o cliente envia o prefácio e o SETTINGS; o HEADERS da requisição original
+// é inserido logo em seguida, como se o cliente o tivesse enviado
+struct H2cUpgrade<I> {
+    inner: I,
+    headers: Option<Vec<u8>>,
+    received: Vec<u8>,
+    pending: Vec<u8>,
+    position: usize,
+}
+
+impl<I> H2cUpgrade<I> {
+    fn new(inner: I, headers: Vec<u8>) -> Self {
+        Self {
+            inner,
+            headers: Some(headers),
+            received: Vec::new(),
+            pending: Vec::new(),
+            position: 0,
+        }
+    }
+
+    // Tamanho do prefácio + primeiro frame, quando já chegou por inteiro
+    fn preamble_len(&self) -> Option<usize> {
+        let header = self
+            .received
+            .get(CONNECTION_PREFACE_LEN..CONNECTION_PREFACE_LEN + FRAME_HEADER_LEN)?;
+        let length = u32::from_be_bytes([0, header[0], header[1], header[2]]) as usize;
+        let total = CONNECTION_PREFACE_LEN + FRAME_HEADER_LEN + length;
+        (self.received.len() >= total).then_some(total)
+    }
+}
+
+impl<I: AsyncRead + Unpin> AsyncRead for H2cUpgrade<I> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = &mut *self;
+        
+        loop {
+            if this.position < this.pending.len() {
+                let n = buf.remaining().min(this.pending.len() - this.position);
+                buf.put_slice(&this.pending[this.position..this.position + n]);
+                this.position += n;
+                return Poll::Ready(Ok(()));
+            }
+            
+            let Some(headers) = this.headers.as_ref() else {
+                return Pin::new(&mut this.inner).poll_read(cx, buf);
+            };
+            
+            if let Some(split) = this.preamble_len() {
+                let mut pending = this.received[..split].to_vec();
+                pending.extend_from_slice(headers);
+                pending.extend_from_slice(&this.received[split..]);
+                this.pending = pending;
+                this.position = 0;
+                this.headers = None;
+                continue;
+            }
+            
+            let mut chunk = [0u8; 4096];
+            let mut chunk_buf = ReadBuf::new(&mut chunk);
+            match Pin::new(&mut this.inner).poll_read(cx, &mut chunk_buf) {
+                Poll::Ready(Ok(())) if chunk_buf.filled().is_empty() => {
+                    // Conexão fechada antes do prefácio completo
+                    this.pending = std::mem::take(&mut this.received);
+                    this.position = 0;
+                    this.headers = None;
+                    if this.pending.is_empty() {
+                        return Poll::Ready(Ok(()));
+                    }
+                }
+                Poll::Ready(Ok(())) => this.received.extend_from_slice(chunk_buf.filled()),
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+impl<I: AsyncWrite + Unpin> AsyncWrite for H2cUpgrade<I> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+async fn serve_io<I>(io: I, app: Router, http2: Http2Config, connection: Arc<Connection>, http2_only: bool)
+where
+    I: hyper::rt::Read + hyper::rt::Write + Unpin + Send + 'static,
+{
+    let builder = http2.builder();
+    let builder = if http2_only { builder.http2_only() } else { builder };
+    let id = connection.id;
+    let service = ConnectionService {
+        app,
+        http2,
+        connection,
+    };
+    let service = hyper::service::service_fn(move |request| service.clone().call(request));
+    
+    if let Err(e) = builder.serve_connection_with_upgrades(io, service).await {
+        tracing::debug!("🔌 Conexão {} encerrada com erro: {}", id, e);
+    }
+}
+
+// Bytes já lidos da conexão são entregues de novo antes do restante do stream
+struct Replay<S> {
+    inner: S,
+    head: Vec<u8>,
+    position: usize,
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for Replay<S> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = &mut *self;
+        
+        if this.position < this.head.len() {
+            let n = buf.remaining().min(this.head.len() - this.position);
+            buf.put_slice(&this.head[this.position..this.position + n]);
+            this.position += n;
+            return Poll::Ready(Ok(()));
+        }
+        Pin::new(&mut this.inner).poll_read(cx, buf)
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for Replay<S> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+// Lê até o fim da primeira linha, que decide se a conexão vai para o /fault/*
+async fn read_first_line<S: AsyncRead + Unpin>(stream: &mut S) -> Vec<u8> {
+    let mut head = Vec::new();
+    let mut chunk = [0u8; 4096];
+    
+    while !head.contains(&b'\n') && head.len() < MAX_FIRST_LINE {
+        match stream.read(&mut chunk).await {
+            Ok(0) | Err(_) => break,
+            Ok(n) => head.extend_from_slice(&chunk[..n]),
+        }
+    }
+    head
+}
+
+// Atende uma conexão já aceita (e, no HTTPS, já com o handshake feito)
+pub async fn serve_connection<S>(mut stream: S, app: Router, state: Arc<AppState>, tls: Option<TlsInfo>)
+where
+    S: RawStream,
+{
+    let Ok(head) = tokio::time::timeout(FIRST_LINE_TIMEOUT, read_first_line(&mut stream)).await else {
+        return;
+    };
+    if head.is_empty() {
+        return;
+    }
+    if let Some(fault) = Fault::from_request_line(&head) {
+        fault::inject(stream, head, fault, &state).await;
+        return;
+    }
+    
+    let connection = Arc::new(Connection {
+        id: next_connection_id(),
+        requests: AtomicU64::new(0),
+        tls,
+        upgrade_request: None,
+    });
+    let stream = Replay {
+        inner: stream,
+        head,
+        position: 0,
+    };
+    serve_io(TokioIo::new(stream), app, state.config.http2.clone(), connection, false).await;
+}
+
+// Listener em texto puro: HTTP/1.1, h2c com prior knowledge e Upgrade: h2c
+pub async fn serve(listener: TcpListener, app: Router, state: Arc<AppState>) {
+    loop {
+        let (stream, _) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                tracing::warn!("⚠️  Falha ao aceitar conexão: {}", e);
+                continue;
+            }
+        };
+        
+        tokio::spawn(serve_connection(stream, app.clone(), state.clone(), None));
+    }
+}
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new().route("/protocol", get(handle_protocol))
+}
+
+async fn handle_protocol(
+    axum::extract::State(state): axum::extract::State<Arc<AppState>>,
+    version: Version,
+    request: Option<Extension<ConnectionRequest>>,
+    tls: Option<Extension<TlsInfo>>,
+) -> Response {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/protocol".to_string());
+    state.metrics.increment_success();
+    
+    let http2 = version == Version::HTTP_2;
+    let mut body = json!({
+        "http_version": format!("{:?}", version),
+        "alpn": tls.and_then(|Extension(tls)| tls.alpn),
+    });
+    
+    if let Some(Extension(request)) = request {
+        body["secure"] = json!(request.secure);
+        body["h2c"] = match request.h2c {
+            H2c::No => json!(null),
+            H2c::PriorKnowledge => json!("prior-knowledge"),
+            H2c::Upgrade => json!("upgrade"),
+        };
+        body["connection_id"] = json!(request.connection_id);
+        body["request_number"] = json!(request.request_number);
+        body["connection_reused"] = json!(request.request_number > 1);
+        
+        // O hyper não expõe o stream do HTTP/2: só o do Upgrade: h2c (sempre 1) e o do QUIC são
+        // conhecidos; nos demais o campo vai nulo, com o motivo, em vez de um palpite
+        match request.stream_id {
+            Some(stream_id) => body["stream_id"] = json!(stream_id),
+            None if http2 => {
+                body["stream_id"] = json!(null);
+                body["stream_id_unavailable"] = json!("hyper does not expose HTTP/2 stream ids");
+            }
+            None => {}
+        }
+    }
+    
+    if http2 {
+        body["settings"] = json!(state.config.http2);
+    } else if version == Version::HTTP_3 {
+        body["settings"] = json!({
+            "max_concurrent_streams": state.config.http3.max_concurrent_streams
+        });
+    }
+    
+    Json(body).into_response()
+}
@@ -0,0 +1,554 @@
+use axum::{
+    body::{Body, Bytes},
+    extract::{Query, Request, State},
+    http::{
+        header::{CONTENT_LENGTH, HOST},
+        HeaderMap, HeaderName, HeaderValue, StatusCode, Uri,
+    },
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+    routing::{get, post},
+    Router,
+};
+use base64::{engine::general_purpose, Engine as _};
+use http_body_util::{BodyExt, Full, Limited};
+use hyper_util::{
+    client::legacy::{connect::HttpConnector, Client},
+    rt::TokioExecutor,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use crate::config::env_or;
+use crate::mock::{ADMIN_PREFIX, MAX_BODY};
+use crate::protocol::HOP_BY_HOP_HEADERS;
+use crate::redact::{Redaction, REDACTED};
+use crate::{extract_request_info, AppState};
+
+// Resposta do upstream guardada em uma gravação
+const MAX_RESPONSE: usize = 10 * 1024 * 1024;
+
+// Nome nas métricas do tráfego repassado ou reproduzido; o caminho cru criaria uma chave por URL
+const METRICS_ENDPOINT: &str = "proxy";
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ProxyMode {
+    Off,
+    Record,
+    Replay,
+}
+
+impl ProxyMode {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Record => "record",
+            Self::Replay => "replay",
+        }
+    }
+}
+
+// Proxy reverso que grava pares requisição/resposta em disco e depois os reproduz
+#[derive(Clone)]
+pub struct ProxyConfig {
+    pub mode: ProxyMode,
+    upstream: String,
+    dir: PathBuf,
+}
+
+impl ProxyConfig {
+    pub fn from_env() -> Self {
+        let mode = match env_or("RUSTJIN_PROXY_MODE", "off").trim().to_lowercase().as_str() {
+            "record" => ProxyMode::Record,
+            "replay" => ProxyMode::Replay,
+            _ => ProxyMode::Off,
+        };
+        
+        Self {
+            mode,
+            upstream: env_or("RUSTJIN_PROXY_UPSTREAM", "").trim_end_matches('/').to_string(),
+            dir: PathBuf::from(env_or("RUSTJIN_RECORDINGS_DIR", "recordings")),
+        }
+    }
+}
+
+// Requisição gravada, no formato de RequestInfo mais o método e o caminho
+#[derive(Clone, Serialize, Deserialize)]
+struct RecordedRequest {
+    method: String,
+    path: String,
+    #[serde(default)]
+    args: HashMap<String, String>,
+    #[serde(default)]
+    headers: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    data: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    json: Option<Value>,
+}
+
+// Header da resposta; em lista para manter valores repetidos, como vários Set-Cookie
+#[derive(Clone, Serialize, Deserialize)]
+struct RecordedHeader {
+    name: String,
+    value: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct RecordedResponse {
+    status: u16,
+    #[serde(default)]
+    headers: Vec<RecordedHeader>,
+    // JSON fica editável em "json"; texto em "data"; binário como data URL em base64
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    data: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    json: Option<Value>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Recording {
+    #[serde(default)]
+    id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    recorded_at: Option<String>,
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+// Linha de /__admin/recordings; no modo record é só isso que fica em memória
+#[derive(Clone, Serialize)]
+struct Summary {
+    id: String,
+    method: String,
+    path: String,
+    status: u16,
+    replayed: u64,
+}
+
+// Valor gravado como [REDACTED] aceita qualquer valor na reprodução
+fn same(recorded: &str, received: &str) -> bool {
+    recorded == REDACTED || recorded == received
+}
+
+fn same_json(recorded: &Value, received: &Value) -> bool {
+    match (recorded, received) {
+        (Value::String(text), _) if text == REDACTED => true,
+        (Value::Object(recorded), Value::Object(received)) => {
+            recorded.len() == received.len()
+                && recorded
+                    .iter()
+                    .all(|(key, value)| received.get(key).is_some_and(|other| same_json(value, other)))
+        }
+        (Value::Array(recorded), Value::Array(received)) => {
+            recorded.len() == received.len()
+                && recorded.iter().zip(received).all(|(value, other)| same_json(value, other))
+        }
+        _ => recorded == received,
+    }
+}
+
+impl Recording {
+    // Método, caminho, query e corpo; headers não entram na comparação
+    fn matches(&self, method: &str, path: &str, info: &RecordedRequest) -> bool {
+        let request = &self.request;
+        request.method.eq_ignore_ascii_case(method)
+            && request.path == path
+            && request.args.len() == info.args.len()
+            && request
+                .args
+                .iter()
+                .all(|(name, value)| info.args.get(name).is_some_and(|other| same(value, other)))
+            && match (&request.json, &info.json, &request.data, &info.data) {
+                (Some(recorded), Some(received), _, _) => same_json(recorded, received),
+                (None, _, Some(recorded), Some(received)) => same(recorded, received),
+                (None, _, None, received) => received.as_deref().unwrap_or("").is_empty(),
+                _ => false,
+            }
+    }
+
+    fn summary(&self, replayed: u64) -> Summary {
+        Summary {
+            id: self.id.clone(),
+            method: self.request.method.clone(),
+            path: self.request.path.clone(),
+            status: self.response.status,
+            replayed,
+        }
+    }
+
+    fn respond(&self) -> Response {
+        let recorded = &self.response;
+        let body = match (&recorded.json, &recorded.data) {
+            (Some(json), _) => Bytes::from(json.to_string()),
+            (None, Some(data)) => decode_data(data),
+            (None, None) => Bytes::new(),
+        };
+        
+        let mut response = (StatusCode::from_u16(recorded.status).unwrap_or(StatusCode::OK), body).into_response();
+        for header in &recorded.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::try_from(header.name.as_str()),
+                HeaderValue::try_from(header.value.as_str()),
+            ) {
+                response.headers_mut().append(name, value);
+            }
+        }
+        response
+    }
+
+    fn file_name(&self) -> String {
+        let slug: String = self
+            .request
+            .path
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .take(60)
+            .collect();
+        format!(
+            "{}-{}{}-{}.json",
+            chrono::Utc::now().format("%Y%m%dT%H%M%S%3f"),
+            self.request.method,
+            slug,
+            &self.id[..8]
+        )
+    }
+}
+
+// Mesmo formato de data URL usado pelo extract_request_info para corpos binários
+fn encode_data(content_type: Option<&str>, body: &[u8]) -> String {
+    match std::str::from_utf8(body) {
+        Ok(text) => text.to_string(),
+        Err(_) => format!(
+            "data:{};base64,{}",
+            content_type.unwrap_or("application/octet-stream"),
+            general_purpose::STANDARD.encode(body)
+        ),
+    }
+}
+
+fn decode_data(data: &str) -> Bytes {
+    data.strip_prefix("data:")
+        .and_then(|rest| rest.split_once(";base64,"))
+        .and_then(|(_, encoded)| general_purpose::STANDARD.decode(encoded).ok())
+        .map(Bytes::from)
+        .unwrap_or_else(|| Bytes::from(data.to_string()))
+}
+
+fn forwardable(headers: &HeaderMap) -> impl Iterator<Item = (&HeaderName, &HeaderValue)> {
+    headers
+        .iter()
+        .filter(|(name, _)| !HOP_BY_HOP_HEADERS.contains(&name.as_str()) && *name != CONTENT_LENGTH)
+}
+
+// O Host do cliente aponta para o RustJin; o hyper usa o do upstream, tirado da URI
+fn forwardable_request(headers: &HeaderMap) -> impl Iterator<Item = (&HeaderName, &HeaderValue)> {
+    forwardable(headers).filter(|(name, _)| *name != HOST)
+}
+
+fn recorded_request(redaction: &Redaction, parts: &axum::http::request::Parts, body: &Bytes) -> RecordedRequest {
+    let args: HashMap<String, String> = parts
+        .uri
+        .query()
+        .and_then(|q| serde_urlencoded::from_str(q).ok())
+        .unwrap_or_default();
+    let query = Query(args);
+    let body = (!body.is_empty()).then(|| body.clone());
+    let info = extract_request_info(redaction, &parts.headers, query, body);
+    
+    RecordedRequest {
+        method: parts.method.to_string(),
+        path: parts.uri.path().to_string(),
+        args: info.args,
+        headers: info.headers,
+        data: info.data,
+        json: info.json,
+    }
+}
+
+// Cliente do upstream, gravações carregadas para a reprodução e resumo das gravadas nesta execução
+#[derive(Clone)]
+pub struct Proxy {
+    client: Client<HttpConnector, Full<Bytes>>,
+    upstream: Option<Uri>,
+    recordings: Arc<Mutex<Vec<(Recording, u64)>>>,
+    recorded: Arc<Mutex<Vec<Summary>>>,
+}
+
+impl Proxy {
+    pub fn load(config: &ProxyConfig) -> Result<Self, String> {
+        let upstream = match config.mode {
+            ProxyMode::Record => {
+                let uri: Uri = config
+                    .upstream
+                    .parse()
+                    .map_err(|_| format!("Invalid RUSTJIN_PROXY_UPSTREAM '{}'", config.upstream))?;
+                if uri.scheme_str() != Some("http") || uri.authority().is_none() {
+                    return Err("RUSTJIN_PROXY_UPSTREAM must be an http:// URL".to_string());
+                }
+                std::fs::create_dir_all(&config.dir).map_err(|e| format!("{}: {}", config.dir.display(), e))?;
+                Some(uri)
+            }
+            _ => None,
+        };
+        
+        let proxy = Self {
+            client: Client::builder(TokioExecutor::new()).build_http(),
+            upstream,
+            recordings: Arc::new(Mutex::new(Vec::new())),
+            recorded: Arc::new(Mutex::new(Vec::new())),
+        };
+        if config.mode == ProxyMode::Replay {
+            proxy.reload(config)?;
+        }
+        Ok(proxy)
+    }
+
+    fn reload(&self, config: &ProxyConfig) -> Result<usize, String> {
+        let mut files: Vec<PathBuf> = std::fs::read_dir(&config.dir)
+            .map_err(|e| format!("{}: {}", config.dir.display(), e))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        files.sort();
+        
+        let recordings = files
+            .iter()
+            .map(|file| {
+                let raw = std::fs::read(file).map_err(|e| format!("{}: {}", file.display(), e))?;
+                serde_json::from_slice::<Recording>(&raw)
+                    .map(|recording| (recording, 0))
+                    .map_err(|e| format!("{}: {}", file.display(), e))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        
+        let total = recordings.len();
+        if let Ok(mut loaded) = self.recordings.lock() {
+            *loaded = recordings;
+        }
+        tracing::info!("📼 {} gravações carregadas de {}", total, config.dir.display());
+        Ok(total)
+    }
+
+    // Entre as gravações que casam, a menos reproduzida: sequências gravadas voltam em ordem
+    fn replay(&self, method: &str, path: &str, info: &RecordedRequest) -> Option<Response> {
+        let mut recordings = self.recordings.lock().ok()?;
+        let (recording, uses) = recordings
+            .iter_mut()
+            .filter(|(recording, _)| recording.matches(method, path, info))
+            .min_by_key(|(_, uses)| *uses)?;
+        *uses += 1;
+        Some(recording.respond())
+    }
+
+    async fn forward(&self, parts: &axum::http::request::Parts, body: Bytes) -> Result<(axum::http::response::Parts, Bytes), String> {
+        let upstream = self.upstream.as_ref().ok_or("No upstream configured")?;
+        let path_and_query = parts.uri.path_and_query().map(|p| p.as_str()).unwrap_or("/");
+        let uri = format!("{}{}", upstream.to_string().trim_end_matches('/'), path_and_query);
+        
+        let mut request = hyper::Request::builder().method(parts.method.clone()).uri(uri);
+        for (name, value) in forwardable_request(&parts.headers) {
+            request = request.header(name, value);
+        }
+        let request = request.body(Full::new(body)).map_err(|e| e.to_string())?;
+        
+        let response = self.client.request(request).await.map_err(|e| e.to_string())?;
+        let (parts, body) = response.into_parts();
+        let body = Limited::new(body, MAX_RESPONSE)
+            .collect()
+            .await
+            .map_err(|e| e.to_string())?
+            .to_bytes();
+        Ok((parts, body))
+    }
+}
+
+pub async fn layer(State(state): State<Arc<AppState>>, request: Request, next: Next) -> Response {
+    let mode = state.config.proxy.mode;
+    if mode == ProxyMode::Off || request.uri().path().starts_with(ADMIN_PREFIX) {
+        return next.run(request).await;
+    }
+    
+    let (parts, body) = request.into_parts();
+    let path = parts.uri.path().to_string();
+    let body = match axum::body::to_bytes(body, MAX_BODY).await {
+        Ok(body) => body,
+        Err(_) => {
+            state.metrics.increment_total();
+            state.metrics.record_endpoint(METRICS_ENDPOINT.to_string());
+            state.metrics.increment_failed();
+            return (
+                StatusCode::PAYLOAD_TOO_LARGE,
+                Json(json!({
+                    "error": "Request body too large",
+                    "max_bytes": MAX_BODY
+                })),
+            )
+                .into_response();
+        }
+    };
+    let recorded = recorded_request(&state.config.redaction, &parts, &body);
+    
+    if mode == ProxyMode::Replay {
+        return match state.proxy.replay(parts.method.as_str(), &path, &recorded) {
+            Some(response) => {
+                state.metrics.increment_total();
+                state.metrics.record_endpoint(METRICS_ENDPOINT.to_string());
+                state.metrics.increment_success();
+                response
+            }
+            None => {
+                tracing::debug!("📼 Sem gravação para {} {}", parts.method, path);
+                next.run(Request::from_parts(parts, Body::from(body))).await
+            }
+        };
+    }
+    
+    state.metrics.increment_total();
+    state.metrics.record_endpoint(METRICS_ENDPOINT.to_string());
+    
+    let (response_parts, response_body) = match state.proxy.forward(&parts, body).await {
+        Ok(response) => response,
+        Err(message) => {
+            state.metrics.increment_failed();
+            tracing::warn!("⚠️  Upstream indisponível para {} {}: {}", parts.method, path, message);
+            return (
+                StatusCode::BAD_GATEWAY,
+                Json(json!({
+                    "error": "Upstream request failed",
+                    "message": message
+                })),
+            )
+                .into_response();
+        }
+    };
+    state.metrics.increment_success();
+    
+    let content_type = response_parts
+        .headers
+        .get(axum::http::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok());
+    let mut headers: Vec<(String, String)> = forwardable(&response_parts.headers)
+        .map(|(name, value)| (name.to_string(), value.to_str().unwrap_or("").to_string()))
+        .collect();
+    state.config.redaction.header_pairs(&mut headers);
+    let headers = headers
+        .into_iter()
+        .map(|(name, value)| RecordedHeader { name, value })
+        .collect();
+    
+    let mut json = serde_json::from_slice::<Value>(&response_body).ok();
+    if let Some(value) = json.as_mut() {
+        state.config.redaction.json(value);
+    }
+    let recording = Recording {
+        id: uuid::Uuid::new_v4().to_string(),
+        recorded_at: Some(chrono::Utc::now().to_rfc3339()),
+        request: recorded,
+        response: RecordedResponse {
+            status: response_parts.status.as_u16(),
+            headers,
+            data: (json.is_none() && !response_body.is_empty()).then(|| encode_data(content_type, &response_body)),
+            json,
+        },
+    };
+    
+    let file = state.config.proxy.dir.join(recording.file_name());
+    match serde_json::to_vec_pretty(&recording) {
+        Ok(contents) => match tokio::fs::write(&file, contents).await {
+            Ok(()) => tracing::info!("📼 Gravado: {} {} -> {}", parts.method, path, file.display()),
+            Err(e) => tracing::warn!("⚠️  Não foi possível gravar {}: {}", file.display(), e),
+        },
+        Err(e) => tracing::warn!("⚠️  Não foi possível serializar a gravação: {}", e),
+    }
+    // O corpo (até MAX_RESPONSE) já está no disco; em memória fica só o resumo
+    if let Ok(mut recorded) = state.proxy.recorded.lock() {
+        recorded.push(recording.summary(0));
+    }
+    
+    let mut response = Response::from_parts(response_parts, Body::from(response_body));
+    for name in HOP_BY_HOP_HEADERS {
+        response.headers_mut().remove(name);
+    }
+    response
+}
+
+pub fn routes(state: &Arc<AppState>) -> Router<Arc<AppState>> {
+    if state.config.proxy.mode == ProxyMode::Off {
+        return Router::new();
+    }
+    
+    Router::new()
+        .route("/__admin/recordings", get(handle_list))
+        .route("/__admin/recordings/reload", post(handle_reload))
+}
+
+async fn handle_list(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/__admin/recordings".to_string());
+    state.metrics.increment_success();
+    
+    let mut recordings: Vec<Summary> = state
+        .proxy
+        .recordings
+        .lock()
+        .map(|recordings| {
+            recordings
+                .iter()
+                .map(|(recording, uses)| recording.summary(*uses))
+                .collect()
+        })
+        .unwrap_or_default();
+    if let Ok(recorded) = state.proxy.recorded.lock() {
+        recordings.extend(recorded.iter().cloned());
+    }
+    
+    Json(json!({
+        "mode": state.config.proxy.mode.name(),
+        "upstream": state.proxy.upstream.as_ref().map(Uri::to_string),
+        "directory": state.config.proxy.dir.display().to_string(),
+        "total": recordings.len(),
+        "recordings": recordings
+    }))
+}
+
+// Relê o diretório depois de editar as gravações; só faz sentido no modo replay
+async fn handle_reload(State(state): State<Arc<AppState>>) -> Response {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/__admin/recordings/reload".to_string());
+    
+    if state.config.proxy.mode != ProxyMode::Replay {
+        state.metrics.increment_failed();
+        return (
+            StatusCode::CONFLICT,
+            Json(json!({
+                "error": "Not in replay mode",
+                "mode": state.config.proxy.mode.name()
+            })),
+        )
+            .into_response();
+    }
+    
+    match state.proxy.reload(&state.config.proxy) {
+        Ok(total) => {
+            state.metrics.increment_success();
+            Json(json!({ "total": total })).into_response()
+        }
+        Err(message) => {
+            state.metrics.increment_failed();
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": "Invalid recording",
+                    "message": message
+                })),
+            )
+                .into_response()
+        }
+    }
+}
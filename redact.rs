@@ -0,0 +1,275 @@
+use serde_json::Value;
+use std::collections::HashMap;
+
+use crate::config::{env_flag, env_list, ApiKeyConfig};
+use crate::session::SESSION_COOKIE;
+
+pub const REDACTED: &str = "[REDACTED]";
+
+const DEFAULT_HEADERS: &str = "authorization,proxy-authorization,cookie,set-cookie";
+const DEFAULT_QUERY_PARAMS: &str = "api_key,apikey,token,access_token,password,secret";
+const DEFAULT_JSON_FIELDS: &str =
+    "password,passwd,secret,token,access_token,refresh_token,api_key,client_secret";
+const DEFAULT_COOKIES: &str = "";
+
+// Política de ocultação de segredos aplicada aos ecos, logs e histórico
+#[derive(Clone)]
+pub struct Redaction {
+    headers: Vec<String>,
+    query_params: Vec<String>,
+    json_fields: Vec<String>,
+    cookies: Vec<String>,
+    full_echo: bool,
+}
+
+impl Redaction {
+    pub fn from_env(api_keys: &ApiKeyConfig) -> Self {
+        let mut headers = env_list("RUSTJIN_REDACT_HEADERS", DEFAULT_HEADERS);
+        let mut query_params = env_list("RUSTJIN_REDACT_QUERY", DEFAULT_QUERY_PARAMS);
+        let mut cookies = env_list("RUSTJIN_REDACT_COOKIES", DEFAULT_COOKIES);
+        
+        // A chave de API e a sessão nunca devem voltar no eco, seja qual for o nome configurado
+        headers.push(api_keys.header_name.to_lowercase());
+        query_params.push(api_keys.query_param.to_lowercase());
+        cookies.push(api_keys.cookie_name.to_lowercase());
+        cookies.push(SESSION_COOKIE.to_string());
+        
+        Self {
+            headers,
+            query_params,
+            json_fields: env_list("RUSTJIN_REDACT_JSON_FIELDS", DEFAULT_JSON_FIELDS),
+            cookies,
+            full_echo: env_flag("RUSTJIN_FULL_ECHO"),
+        }
+    }
+
+    pub fn full_echo(&self) -> bool {
+        self.full_echo
+    }
+
+    // Valor a exibir no lugar de um segredo (o próprio valor em modo de eco completo)
+    pub fn secret<'a>(&self, value: &'a str) -> &'a str {
+        if self.full_echo {
+            value
+        } else {
+            REDACTED
+        }
+    }
+
+    pub fn headers(&self, headers: &mut HashMap<String, String>) {
+        redact_map(headers, &self.headers, self.full_echo);
+    }
+
+    // Headers em lista, onde o mesmo nome pode se repetir (Set-Cookie)
+    pub fn header_pairs(&self, headers: &mut [(String, String)]) {
+        if self.full_echo {
+            return;
+        }
+        
+        for (name, value) in headers.iter_mut() {
+            if self.headers.contains(&name.to_lowercase()) {
+                *value = REDACTED.to_string();
+            }
+        }
+    }
+
+    pub fn query(&self, args: &mut HashMap<String, String>) {
+        redact_map(args, &self.query_params, self.full_echo);
+    }
+
+    pub fn cookies(&self, cookies: &mut HashMap<String, String>) {
+        redact_map(cookies, &self.cookies, self.full_echo);
+    }
+
+    // Corpo de formulário: os mesmos campos do JSON; retorna true se algo mudou
+    pub fn form(&self, pairs: &mut [(String, String)]) -> bool {
+        if self.full_echo {
+            return false;
+        }
+        
+        let mut changed = false;
+        for (name, value) in pairs.iter_mut() {
+            if self.json_fields.contains(&name.to_lowercase()) {
+                *value = REDACTED.to_string();
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    // Oculta campos sensíveis em qualquer nível do documento; retorna true se algo mudou
+    pub fn json(&self, value: &mut Value) -> bool {
+        if self.full_echo {
+            return false;
+        }
+        
+        match value {
+            Value::Object(map) => {
+                let mut changed = false;
+                for (key, field) in map.iter_mut() {
+                    if self.json_fields.contains(&key.to_lowercase()) {
+                        *field = Value::String(REDACTED.to_string());
+                        changed = true;
+                    } else {
+                        changed |= self.json(field);
+                    }
+                }
+                changed
+            }
+            Value::Array(items) => {
+                let mut changed = false;
+                for item in items.iter_mut() {
+                    changed |= self.json(item);
+                }
+                changed
+            }
+            _ => false,
+        }
+    }
+
+    // Versão de uma URL segura para logs, com os parâmetros sensíveis ocultos
+    pub fn url(&self, url: &str) -> String {
+        if self.full_echo {
+            return url.to_string();
+        }
+        
+        let Some((base, query)) = url.split_once('?') else {
+            return url.to_string();
+        };
+        
+        let query: Vec<String> = query
+            .split('&')
+            .map(|pair| match pair.split_once('=') {
+                Some((name, _)) if self.query_params.contains(&name.to_lowercase()) => {
+                    format!("{}={}", name, REDACTED)
+                }
+                _ => pair.to_string(),
+            })
+            .collect();
+        
+        format!("{}?{}", base, query.join("&"))
+    }
+}
+
+fn redact_map(map: &mut HashMap<String, String>, names: &[String], full_echo: bool) {
+    if full_echo {
+        return;
+    }
+    
+    for (name, value) in map.iter_mut() {
+        if names.contains(&name.to_lowercase()) {
+            *value = REDACTED.to_string();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn policy(full_echo: bool) -> Redaction {
+        let list = |raw: &str| raw.split(',').map(str::to_string).collect();
+        Redaction {
+            headers: list(DEFAULT_HEADERS),
+            query_params: list(DEFAULT_QUERY_PARAMS),
+            json_fields: list(DEFAULT_JSON_FIELDS),
+            cookies: list("api_key,rustjin_session"),
+            full_echo,
+        }
+    }
+
+    fn map(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn headers_are_redacted_case_insensitively() {
+        let mut headers = map(&[("Authorization", "Bearer abc"), ("accept", "*/*")]);
+        policy(false).headers(&mut headers);
+        
+        assert_eq!(headers["Authorization"], REDACTED);
+        assert_eq!(headers["accept"], "*/*");
+    }
+
+    #[test]
+    fn header_pairs_redact_every_repeated_value() {
+        let pair = |name: &str, value: &str| (name.to_string(), value.to_string());
+        let mut headers = vec![pair("Set-Cookie", "a=1"), pair("set-cookie", "b=2"), pair("vary", "accept")];
+        policy(false).header_pairs(&mut headers);
+        
+        assert_eq!(headers, vec![pair("Set-Cookie", REDACTED), pair("set-cookie", REDACTED), pair("vary", "accept")]);
+    }
+
+    #[test]
+    fn cookies_are_redacted_by_name() {
+        let mut cookies = map(&[("api_key", "k"), ("rustjin_session", "s"), ("theme", "dark")]);
+        policy(false).cookies(&mut cookies);
+        
+        assert_eq!(cookies["api_key"], REDACTED);
+        assert_eq!(cookies["rustjin_session"], REDACTED);
+        assert_eq!(cookies["theme"], "dark");
+    }
+
+    #[test]
+    fn url_redacts_only_sensitive_query_params() {
+        let redaction = policy(false);
+        
+        assert_eq!(
+            redaction.url("https://example.com/cb?Token=abc&page=2"),
+            "https://example.com/cb?Token=[REDACTED]&page=2"
+        );
+        assert_eq!(redaction.url("https://example.com/plain"), "https://example.com/plain");
+    }
+
+    #[test]
+    fn json_redacts_nested_fields() {
+        let mut value = json!({
+            "user": "ana",
+            "credentials": { "Password": "hunter2" },
+            "tokens": [{ "refresh_token": "r" }]
+        });
+        
+        assert!(policy(false).json(&mut value));
+        assert_eq!(value["user"], "ana");
+        assert_eq!(value["credentials"]["Password"], REDACTED);
+        assert_eq!(value["tokens"][0]["refresh_token"], REDACTED);
+    }
+
+    #[test]
+    fn json_without_secrets_is_unchanged() {
+        let mut value = json!({ "user": "ana", "items": [1, 2] });
+        
+        assert!(!policy(false).json(&mut value));
+        assert_eq!(value, json!({ "user": "ana", "items": [1, 2] }));
+    }
+
+    #[test]
+    fn form_redacts_json_fields() {
+        let mut pairs = vec![
+            ("password".to_string(), "hunter2".to_string()),
+            ("token".to_string(), "abc".to_string()),
+            ("user".to_string(), "ana".to_string()),
+        ];
+        
+        assert!(policy(false).form(&mut pairs));
+        assert_eq!(pairs[0].1, REDACTED);
+        assert_eq!(pairs[1].1, REDACTED);
+        assert_eq!(pairs[2].1, "ana");
+    }
+
+    #[test]
+    fn full_echo_disables_redaction() {
+        let redaction = policy(true);
+        let mut headers = map(&[("authorization", "Bearer abc")]);
+        let mut value = json!({ "password": "hunter2" });
+        let mut pairs = vec![("password".to_string(), "hunter2".to_string())];
+        
+        redaction.headers(&mut headers);
+        assert_eq!(headers["authorization"], "Bearer abc");
+        assert!(!redaction.json(&mut value));
+        assert!(!redaction.form(&mut pairs));
+        assert_eq!(redaction.url("/cb?token=abc"), "/cb?token=abc");
+        assert_eq!(redaction.secret("abc"), "abc");
+    }
+}
@@ -0,0 +1,566 @@
+use axum::{
+    body::Bytes,
+    extract::Extension,
+    http::{
+        header::{CONTENT_TYPE, LOCATION, SET_COOKIE},
+        HeaderMap, HeaderValue, StatusCode,
+    },
+    response::{IntoResponse, Response},
+    Json,
+};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine as _};
+use cookie::{time::Duration as CookieDuration, Cookie, SameSite};
+use hmac::{Hmac, Mac};
+use serde::Deserialize;
+use serde_json::json;
+use sha2::Sha256;
+use std::{collections::HashMap, sync::Arc, sync::Mutex};
+
+use crate::{config::env_or, cookies, tls::TlsInfo, AppState};
+
+type HmacSha256 = Hmac<Sha256>;
+
+pub const SESSION_COOKIE: &str = "rustjin_session";
+pub const CSRF_COOKIE: &str = "rustjin_csrf";
+
+// Limites do RUSTJIN_SESSION_TTL: valores fora disso estouram as contas de data do chrono
+const MIN_TTL_SECONDS: i64 = 60;
+const MAX_TTL_SECONDS: i64 = 30 * 24 * 60 * 60;
+
+// Sessões simultâneas em memória; acima disso a mais antiga é descartada
+const MAX_SESSIONS: usize = 10_000;
+
+// Configuração do simulador de login
+#[derive(Clone)]
+pub struct SessionConfig {
+    secret: Vec<u8>,
+    ttl_seconds: i64,
+    username: String,
+    password: String,
+}
+
+impl SessionConfig {
+    pub fn from_env() -> Self {
+        // Sem segredo configurado, as sessões valem apenas enquanto o processo estiver de pé
+        let secret = std::env::var("RUSTJIN_SESSION_SECRET")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .map(String::into_bytes)
+            .unwrap_or_else(|| {
+                [uuid::Uuid::new_v4().into_bytes(), uuid::Uuid::new_v4().into_bytes()].concat()
+            });
+        
+        Self {
+            secret,
+            ttl_seconds: env_or("RUSTJIN_SESSION_TTL", "1800")
+                .parse::<i64>()
+                .unwrap_or(1800)
+                .clamp(MIN_TTL_SECONDS, MAX_TTL_SECONDS),
+            username: env_or("RUSTJIN_SESSION_USER", "user"),
+            password: env_or("RUSTJIN_SESSION_PASSWORD", "passwd"),
+        }
+    }
+
+    fn sign(&self, value: &str) -> String {
+        let mut mac = HmacSha256::new_from_slice(&self.secret)
+            .expect("HMAC aceita chaves de qualquer tamanho");
+        mac.update(value.as_bytes());
+        format!("{}.{}", value, URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes()))
+    }
+
+    // Retorna o valor original se a assinatura conferir
+    fn verify<'a>(&self, signed: &'a str) -> Option<&'a str> {
+        let (value, signature) = signed.rsplit_once('.')?;
+        let signature = URL_SAFE_NO_PAD.decode(signature).ok()?;
+        
+        let mut mac = HmacSha256::new_from_slice(&self.secret).ok()?;
+        mac.update(value.as_bytes());
+        mac.verify_slice(&signature).ok().map(|_| value)
+    }
+}
+
+#[derive(Clone)]
+pub struct Session {
+    id: String,
+    username: String,
+    csrf_token: String,
+    created_at: chrono::DateTime<chrono::Utc>,
+    expires_at: chrono::DateTime<chrono::Utc>,
+    rotations: u32,
+}
+
+impl Session {
+    fn is_expired(&self) -> bool {
+        chrono::Utc::now() >= self.expires_at
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        json!({
+            "authenticated": true,
+            "username": self.username,
+            "csrf_token": self.csrf_token,
+            "created_at": self.created_at.to_rfc3339(),
+            "expires_at": self.expires_at.to_rfc3339(),
+            "rotations": self.rotations
+        })
+    }
+}
+
+// Sessões ativas em memória
+#[derive(Clone, Default)]
+pub struct SessionStore {
+    sessions: Arc<Mutex<HashMap<String, Session>>>,
+}
+
+impl SessionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn create(&self, config: &SessionConfig, username: &str, rotations: u32) -> Session {
+        let now = chrono::Utc::now();
+        let session = Session {
+            id: new_token(),
+            username: username.to_string(),
+            csrf_token: new_token(),
+            created_at: now,
+            expires_at: now + chrono::Duration::seconds(config.ttl_seconds),
+            rotations,
+        };
+        
+        if let Ok(mut sessions) = self.sessions.lock() {
+            sessions.retain(|_, s| !s.is_expired());
+            if sessions.len() >= MAX_SESSIONS {
+                let oldest = sessions
+                    .values()
+                    .min_by_key(|s| s.created_at)
+                    .map(|s| s.id.clone());
+                if let Some(oldest) = oldest {
+                    sessions.remove(&oldest);
+                }
+            }
+            sessions.insert(session.id.clone(), session.clone());
+        }
+        
+        session
+    }
+
+    fn get(&self, id: &str) -> Option<Session> {
+        let mut sessions = self.sessions.lock().ok()?;
+        let session = sessions.get(id)?.clone();
+        
+        if session.is_expired() {
+            sessions.remove(id);
+            return None;
+        }
+        
+        Some(session)
+    }
+
+    fn remove(&self, id: &str) -> Option<Session> {
+        self.sessions.lock().ok()?.remove(id)
+    }
+}
+
+// Comparação em tempo constante, para o tempo de resposta não revelar o token esperado
+fn same_token(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected
+            .bytes()
+            .zip(given.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn new_token() -> String {
+    URL_SAFE_NO_PAD.encode(
+        [uuid::Uuid::new_v4().into_bytes(), uuid::Uuid::new_v4().into_bytes()].concat(),
+    )
+}
+
+// Campos aceitos em formulário ou JSON nas rotas de sessão
+#[derive(Deserialize, Default)]
+struct SessionForm {
+    username: Option<String>,
+    password: Option<String>,
+    csrf_token: Option<String>,
+}
+
+struct SessionRequest {
+    form: SessionForm,
+    is_form: bool,
+}
+
+fn parse_body(headers: &HeaderMap, body: &Bytes) -> SessionRequest {
+    let content_type = headers
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("");
+    
+    if content_type.starts_with("application/json") {
+        return SessionRequest {
+            form: serde_json::from_slice(body).unwrap_or_default(),
+            is_form: false,
+        };
+    }
+    
+    SessionRequest {
+        form: serde_urlencoded::from_bytes(body).unwrap_or_default(),
+        is_form: content_type.starts_with("application/x-www-form-urlencoded"),
+    }
+}
+
+fn csrf_from_request(headers: &HeaderMap, form: &SessionForm) -> Option<String> {
+    headers
+        .get("x-csrf-token")
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+        .or_else(|| form.csrf_token.clone())
+}
+
+fn current_session(state: &AppState, headers: &HeaderMap) -> Option<Session> {
+    let cookie = cookies::parse_cookie_header(headers).remove(SESSION_COOKIE)?;
+    let id = state.config.session.verify(&cookie)?;
+    state.sessions.get(id)
+}
+
+// Por HTTPS (ou HTTP/3) os cookies saem com Secure
+fn session_cookie(state: &AppState, session: &Session, secure: bool) -> Cookie<'static> {
+    Cookie::build((SESSION_COOKIE, state.config.session.sign(&session.id)))
+        .path("/")
+        .http_only(true)
+        .secure(secure)
+        .same_site(SameSite::Lax)
+        .max_age(CookieDuration::seconds(state.config.session.ttl_seconds))
+        .build()
+}
+
+fn removal_cookie(name: &'static str, path: &'static str) -> Cookie<'static> {
+    let mut cookie = Cookie::build((name, "")).path(path).build();
+    cookie.make_removal();
+    cookie
+}
+
+fn with_cookies(mut response: Response, cookies: &[Cookie<'static>]) -> Response {
+    for cookie in cookies {
+        if let Ok(value) = HeaderValue::from_str(&cookie.to_string()) {
+            response.headers_mut().append(SET_COOKIE, value);
+        }
+    }
+    response
+}
+
+fn error(state: &AppState, status: StatusCode, error: &str, message: &str) -> Response {
+    state.metrics.increment_failed();
+    (status, Json(json!({ "error": error, "message": message }))).into_response()
+}
+
+fn see_other(location: &'static str) -> Response {
+    (StatusCode::SEE_OTHER, [(LOCATION, location)]).into_response()
+}
+
+// Handlers
+
+pub async fn handle_login_page(
+    axum::extract::State(state): axum::extract::State<Arc<AppState>>,
+    tls: Option<Extension<TlsInfo>>,
+) -> impl IntoResponse {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/session/login".to_string());
+    
+    // Double submit: o mesmo token vai no cookie assinado e no campo oculto do formulário
+    let csrf_token = new_token();
+    let csrf_cookie = Cookie::build((CSRF_COOKIE, state.config.session.sign(&csrf_token)))
+        .path("/session")
+        .http_only(true)
+        .secure(tls.is_some())
+        .same_site(SameSite::Strict)
+        .build();
+    
+    state.metrics.increment_success();
+    
+    with_cookies(
+        (
+            StatusCode::OK,
+            [(CONTENT_TYPE, "text/html; charset=utf-8")],
+            login_page(&csrf_token, None),
+        )
+            .into_response(),
+        &[csrf_cookie],
+    )
+}
+
+pub async fn handle_login(
+    axum::extract::State(state): axum::extract::State<Arc<AppState>>,
+    tls: Option<Extension<TlsInfo>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/session/login".to_string());
+    
+    let request = parse_body(&headers, &body);
+    let config = &state.config.session;
+    
+    let expected_csrf = cookies::parse_cookie_header(&headers)
+        .remove(CSRF_COOKIE)
+        .and_then(|cookie| config.verify(&cookie).map(str::to_string));
+    let csrf_ok = match (expected_csrf, csrf_from_request(&headers, &request.form)) {
+        (Some(expected), Some(given)) => same_token(&expected, &given),
+        _ => false,
+    };
+    
+    if !csrf_ok {
+        tracing::warn!("🛡️  Login recusado: token CSRF ausente ou inválido");
+        return error(
+            &state,
+            StatusCode::FORBIDDEN,
+            "Invalid CSRF token",
+            "GET /session/login first and submit its csrf_token with the credentials",
+        );
+    }
+    
+    let username = request.form.username.as_deref().unwrap_or("");
+    let password = request.form.password.as_deref().unwrap_or("");
+    
+    if username != config.username || password != config.password {
+        state.metrics.increment_failed();
+        tracing::warn!("❌ Falha no login da sessão para: {}", username);
+        
+        if request.is_form {
+            let csrf_token = csrf_from_request(&headers, &request.form).unwrap_or_default();
+            return (
+                StatusCode::UNAUTHORIZED,
+                [(CONTENT_TYPE, "text/html; charset=utf-8")],
+                login_page(&csrf_token, Some("Usuário ou senha inválidos")),
+            )
+                .into_response();
+        }
+        
+        return (
+            StatusCode::UNAUTHORIZED,
+            Json(json!({
+                "error": "Invalid credentials",
+                "message": "Username or password is incorrect"
+            })),
+        )
+            .into_response();
+    }
+    
+    // Um novo ID a cada login evita fixação de sessão
+    if let Some(previous) = current_session(&state, &headers) {
+        state.sessions.remove(&previous.id);
+    }
+    
+    let session = state.sessions.create(config, username, 0);
+    state.metrics.increment_success();
+    tracing::info!("✅ Sessão iniciada para: {}", username);
+    
+    let response = if request.is_form {
+        see_other("/session/me")
+    } else {
+        Json(session.to_json()).into_response()
+    };
+    
+    with_cookies(
+        response,
+        &[
+            session_cookie(&state, &session, tls.is_some()),
+            removal_cookie(CSRF_COOKIE, "/session"),
+        ],
+    )
+}
+
+pub async fn handle_me(
+    axum::extract::State(state): axum::extract::State<Arc<AppState>>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/session/me".to_string());
+    
+    match current_session(&state, &headers) {
+        Some(session) => {
+            state.metrics.increment_success();
+            Json(session.to_json()).into_response()
+        }
+        None => error(
+            &state,
+            StatusCode::UNAUTHORIZED,
+            "Not authenticated",
+            "Session cookie is missing, invalid or expired",
+        ),
+    }
+}
+
+// Rotas que alteram estado exigem sessão válida e o token CSRF dela
+fn authorize_change(
+    state: &AppState,
+    headers: &HeaderMap,
+    body: &Bytes,
+) -> Result<(Session, bool), Box<Response>> {
+    let request = parse_body(headers, body);
+    
+    let Some(session) = current_session(state, headers) else {
+        return Err(Box::new(error(
+            state,
+            StatusCode::UNAUTHORIZED,
+            "Not authenticated",
+            "Session cookie is missing, invalid or expired",
+        )));
+    };
+    
+    let csrf_ok = csrf_from_request(headers, &request.form)
+        .is_some_and(|given| same_token(&session.csrf_token, &given));
+    if !csrf_ok {
+        tracing::warn!("🛡️  Token CSRF inválido para a sessão de: {}", session.username);
+        return Err(Box::new(error(
+            state,
+            StatusCode::FORBIDDEN,
+            "Invalid CSRF token",
+            "Send the session csrf_token in the X-CSRF-Token header or csrf_token field",
+        )));
+    }
+    
+    Ok((session, request.is_form))
+}
+
+pub async fn handle_rotate(
+    axum::extract::State(state): axum::extract::State<Arc<AppState>>,
+    tls: Option<Extension<TlsInfo>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/session/rotate".to_string());
+    
+    let (session, _) = match authorize_change(&state, &headers, &body) {
+        Ok(authorized) => authorized,
+        Err(response) => return *response,
+    };
+    
+    state.sessions.remove(&session.id);
+    let rotated = state
+        .sessions
+        .create(&state.config.session, &session.username, session.rotations + 1);
+    
+    state.metrics.increment_success();
+    tracing::info!("🔄 Sessão rotacionada para: {}", session.username);
+    
+    with_cookies(
+        Json(rotated.to_json()).into_response(),
+        &[session_cookie(&state, &rotated, tls.is_some())],
+    )
+}
+
+pub async fn handle_logout(
+    axum::extract::State(state): axum::extract::State<Arc<AppState>>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> impl IntoResponse {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/session/logout".to_string());
+    
+    let (session, is_form) = match authorize_change(&state, &headers, &body) {
+        Ok(authorized) => authorized,
+        Err(response) => return *response,
+    };
+    
+    state.sessions.remove(&session.id);
+    state.metrics.increment_success();
+    tracing::info!("👋 Sessão encerrada para: {}", session.username);
+    
+    let response = if is_form {
+        see_other("/session/login")
+    } else {
+        Json(json!({ "authenticated": false, "message": "Logged out" })).into_response()
+    };
+    
+    with_cookies(response, &[removal_cookie(SESSION_COOKIE, "/")])
+}
+
+fn login_page(csrf_token: &str, error: Option<&str>) -> String {
+    let error = error
+        .map(|message| format!(r#"<p class="error" role="alert">{}</p>"#, message))
+        .unwrap_or_default();
+    
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="pt-BR">
+<head>
+    <meta charset="utf-8">
+    <title>RustJin - Login</title>
+    <style>
+        body {{ background: #111; color: #eee; font-family: sans-serif; display: flex; justify-content: center; padding-top: 10vh; }}
+        form {{ background: #1c1c1c; padding: 2rem; border-radius: 8px; width: 320px; }}
+        label, input, button {{ display: block; width: 100%; box-sizing: border-box; margin-bottom: 1rem; }}
+        input {{ padding: .5rem; background: #222; color: #eee; border: 1px solid #444; }}
+        button {{ padding: .6rem; background: #c0392b; color: #fff; border: 0; cursor: pointer; }}
+        .error {{ color: #e74c3c; }}
+    </style>
+</head>
+<body>
+    <form id="login-form" method="post" action="/session/login">
+        <h1>😈 RustJin Login</h1>
+        {error}
+        <label for="username">Usuário</label>
+        <input id="username" name="username" autocomplete="username" required>
+        <label for="password">Senha</label>
+        <input id="password" name="password" type="password" autocomplete="current-password" required>
+        <input type="hidden" name="csrf_token" value="{csrf_token}">
+        <button id="login-submit" type="submit">Entrar</button>
+    </form>
+</body>
+</html>"#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> SessionConfig {
+        SessionConfig {
+            secret: b"test-secret".to_vec(),
+            ttl_seconds: 1800,
+            username: "user".to_string(),
+            password: "passwd".to_string(),
+        }
+    }
+
+    #[test]
+    fn tokens_compare_by_content_and_length() {
+        assert!(same_token("abc", "abc"));
+        assert!(!same_token("abc", "abd"));
+        assert!(!same_token("abc", "ab"));
+        assert!(!same_token("", "a"));
+    }
+
+    #[test]
+    fn signed_values_round_trip_and_reject_tampering() {
+        let config = config();
+        let signed = config.sign("session-id");
+        
+        assert_eq!(config.verify(&signed), Some("session-id"));
+        assert_eq!(config.verify(&signed.replacen("session", "Session", 1)), None);
+        assert_eq!(config.verify("session-id"), None);
+    }
+
+    #[test]
+    fn store_evicts_the_oldest_session_when_full() {
+        let config = config();
+        let store = SessionStore::new();
+        let oldest = store.create(&config, "user", 0);
+        if let Ok(mut sessions) = store.sessions.lock() {
+            if let Some(session) = sessions.get_mut(&oldest.id) {
+                session.created_at -= chrono::Duration::hours(1);
+            }
+        }
+        
+        for _ in 0..MAX_SESSIONS {
+            store.create(&config, "user", 0);
+        }
+        
+        assert_eq!(store.sessions.lock().map(|s| s.len()).unwrap_or(0), MAX_SESSIONS);
+        assert!(store.get(&oldest.id).is_none());
+    }
+}
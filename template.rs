@@ -0,0 +1,295 @@
+use serde_json::Value;
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use crate::mock::json_path;
+use crate::RequestInfo;
+
+// Marcadores de uma expressão: "{{request.query.page}}"
+const OPEN: &str = "{{";
+const CLOSE: &str = "}}";
+
+// Contadores nomeados ({{counter.nome}}), compartilhados por todas as respostas
+#[derive(Clone, Default)]
+pub struct Counters {
+    values: Arc<Mutex<HashMap<String, u64>>>,
+}
+
+impl Counters {
+    fn next(&self, name: &str) -> u64 {
+        self.values
+            .lock()
+            .map(|mut values| {
+                let value = values.entry(name.to_string()).or_insert(0);
+                *value += 1;
+                *value
+            })
+            .unwrap_or(0)
+    }
+
+    pub fn reset(&self) {
+        if let Ok(mut values) = self.values.lock() {
+            values.clear();
+        }
+    }
+}
+
+// Dados disponíveis para uma resposta; os mesmos de extract_request_info, mais a rota
+pub struct Context<'a> {
+    info: &'a RequestInfo,
+    path: &'a str,
+    captures: &'a HashMap<String, String>,
+    counter: u64,
+    counters: &'a Counters,
+    // Um contador nomeado avança uma vez por resposta, mesmo usado várias vezes
+    seen: RefCell<HashMap<String, u64>>,
+}
+
+impl<'a> Context<'a> {
+    pub fn new(
+        info: &'a RequestInfo,
+        path: &'a str,
+        captures: &'a HashMap<String, String>,
+        counter: u64,
+        counters: &'a Counters,
+    ) -> Self {
+        Self {
+            info,
+            path,
+            captures,
+            counter,
+            counters,
+            seen: RefCell::new(HashMap::new()),
+        }
+    }
+
+    fn resolve(&self, expression: &str) -> Option<Value> {
+        let text = |value: Option<&String>| value.map(|v| Value::String(v.clone()));
+        let (root, rest) = split(expression);
+        
+        match (root, rest) {
+            ("uuid", None) => Some(Value::String(uuid::Uuid::new_v4().to_string())),
+            ("now", None) => Some(Value::String(chrono::Utc::now().to_rfc3339())),
+            ("now", Some("epoch")) => Some(chrono::Utc::now().timestamp().into()),
+            ("now", Some("epoch_ms")) => Some(chrono::Utc::now().timestamp_millis().into()),
+            ("now", Some("date")) => Some(Value::String(chrono::Utc::now().format("%Y-%m-%d").to_string())),
+            ("counter", None) => Some(self.counter.into()),
+            ("counter", Some(name)) => {
+                let mut seen = self.seen.borrow_mut();
+                let value = *seen
+                    .entry(name.to_string())
+                    .or_insert_with(|| self.counters.next(name));
+                Some(value.into())
+            }
+            ("request", Some(field)) => {
+                let info = self.info;
+                match split(field) {
+                    ("method", None) => info.method.clone().map(Value::String),
+                    ("path", None) => Some(Value::String(self.path.to_string())),
+                    ("path", Some(name)) => text(self.captures.get(name)),
+                    ("query", Some(name)) => text(info.args.get(name)),
+                    ("headers", Some(name)) => text(info.headers.get(&name.to_lowercase())),
+                    ("origin", None) => Some(Value::String(info.origin.clone())),
+                    ("body", None) => text(info.data.as_ref()),
+                    ("json", path) => info
+                        .json
+                        .as_ref()
+                        .and_then(|json| json_path(json, path.unwrap_or("$")))
+                        .cloned(),
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+}
+
+fn split(expression: &str) -> (&str, Option<&str>) {
+    match expression.split_once('.') {
+        Some((root, rest)) => (root, Some(rest)),
+        None => (expression, None),
+    }
+}
+
+// Expressões de um texto, na ordem em que aparecem
+fn expressions(text: &str) -> Result<Vec<(usize, usize, &str)>, String> {
+    let mut found = Vec::new();
+    let mut offset = 0;
+    
+    while let Some(start) = text[offset..].find(OPEN).map(|i| offset + i) {
+        let end = text[start..]
+            .find(CLOSE)
+            .map(|i| start + i + CLOSE.len())
+            .ok_or_else(|| format!("Unterminated expression at byte {}", start))?;
+        found.push((start, end, text[start + OPEN.len()..end - CLOSE.len()].trim()));
+        offset = end;
+    }
+    Ok(found)
+}
+
+// Recusa no cadastro expressões que nunca poderiam ser resolvidas
+pub fn validate(text: &str) -> Result<(), String> {
+    for (_, _, expression) in expressions(text)? {
+        let known = match split(expression) {
+            ("uuid", None) | ("now", None) | ("counter", _) => true,
+            ("now", Some(field)) => matches!(field, "epoch" | "epoch_ms" | "date"),
+            ("request", Some(field)) => matches!(
+                split(field),
+                ("method" | "path" | "origin" | "body", None)
+                    | ("path" | "query" | "headers", Some(_))
+                    | ("json", _)
+            ),
+            _ => false,
+        };
+        if !known {
+            return Err(format!("Unknown template expression '{{{{{}}}}}'", expression));
+        }
+    }
+    Ok(())
+}
+
+pub fn validate_value(value: &Value) -> Result<(), String> {
+    match value {
+        Value::String(text) => validate(text),
+        Value::Array(items) => items.iter().try_for_each(validate_value),
+        Value::Object(map) => map.values().try_for_each(validate_value),
+        _ => Ok(()),
+    }
+}
+
+// Valores ausentes viram texto vazio; JSON não textual é serializado
+pub fn render(text: &str, context: &Context) -> String {
+    let Ok(found) = expressions(text) else {
+        return text.to_string();
+    };
+    
+    let mut rendered = String::with_capacity(text.len());
+    let mut last = 0;
+    for (start, end, expression) in found {
+        rendered.push_str(&text[last..start]);
+        match context.resolve(expression) {
+            Some(Value::String(value)) => rendered.push_str(&value),
+            Some(Value::Null) | None => {}
+            Some(value) => rendered.push_str(&value.to_string()),
+        }
+        last = end;
+    }
+    rendered.push_str(&text[last..]);
+    rendered
+}
+
+// Um texto que é só uma expressão mantém o tipo do valor ("{{request.json.items}}" vira lista)
+pub fn render_value(value: &Value, context: &Context) -> Value {
+    match value {
+        Value::String(text) => match expressions(text).as_deref() {
+            Ok([(0, end, expression)]) if *end == text.len() => {
+                context.resolve(expression).unwrap_or(Value::Null)
+            }
+            _ => Value::String(render(text, context)),
+        },
+        Value::Array(items) => Value::Array(items.iter().map(|item| render_value(item, context)).collect()),
+        Value::Object(map) => Value::Object(
+            map.iter()
+                .map(|(key, value)| (key.clone(), render_value(value, context)))
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn info() -> RequestInfo {
+        RequestInfo {
+            method: Some("POST".to_string()),
+            args: HashMap::from([("page".to_string(), "2".to_string())]),
+            headers: HashMap::from([("x-user".to_string(), "ana".to_string())]),
+            origin: "127.0.0.1".to_string(),
+            url: "http://localhost/orders/42?page=2".to_string(),
+            data: Some(r#"{"items":[{"sku":"a"}],"total":3}"#.to_string()),
+            json: Some(json!({ "items": [{ "sku": "a" }], "total": 3 })),
+            form: None,
+            decode_error: None,
+        }
+    }
+
+    #[test]
+    fn validate_accepts_known_expressions() {
+        assert!(validate("plain text").is_ok());
+        assert!(validate("{{uuid}} {{now}} {{now.epoch_ms}} {{counter}} {{counter.orders}}").is_ok());
+        assert!(validate("{{request.method}} {{request.path.id}} {{ request.query.page }}").is_ok());
+        assert!(validate("{{request.headers.X-User}} {{request.json}} {{request.json.items[0].sku}}").is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_unknown_or_unterminated() {
+        assert!(validate("{{nope}}").is_err());
+        assert!(validate("{{now.week}}").is_err());
+        assert!(validate("{{request.query}}").is_err());
+        assert!(validate("{{request.method.name}}").is_err());
+        assert!(validate("{{request.path").is_err());
+        assert!(validate_value(&json!({ "a": ["ok", "{{bad}}"] })).is_err());
+    }
+
+    #[test]
+    fn render_substitutes_request_fields() {
+        let info = info();
+        let captures = HashMap::from([("id".to_string(), "42".to_string())]);
+        let counters = Counters::default();
+        let context = Context::new(&info, "/orders/42", &captures, 5, &counters);
+        
+        assert_eq!(
+            render("{{request.method}} {{request.path}} id={{request.path.id}} page={{request.query.page}}", &context),
+            "POST /orders/42 id=42 page=2"
+        );
+        assert_eq!(render("{{request.headers.X-User}}|{{request.query.missing}}|", &context), "ana||");
+        assert_eq!(render("{{request.json.total}} {{request.json.items[0].sku}}", &context), "3 a");
+        assert_eq!(render("#{{counter}}", &context), "#5");
+        // Texto com expressão aberta é devolvido como veio
+        assert_eq!(render("{{request.path", &context), "{{request.path");
+    }
+
+    #[test]
+    fn named_counter_advances_once_per_response() {
+        let info = info();
+        let captures = HashMap::new();
+        let counters = Counters::default();
+        
+        let first = Context::new(&info, "/", &captures, 1, &counters);
+        assert_eq!(render("{{counter.orders}}-{{counter.orders}}", &first), "1-1");
+        let second = Context::new(&info, "/", &captures, 2, &counters);
+        assert_eq!(render("{{counter.orders}}", &second), "2");
+        
+        counters.reset();
+        let third = Context::new(&info, "/", &captures, 3, &counters);
+        assert_eq!(render("{{counter.orders}}", &third), "1");
+    }
+
+    #[test]
+    fn render_value_keeps_type_of_single_expression() {
+        let info = info();
+        let captures = HashMap::new();
+        let counters = Counters::default();
+        let context = Context::new(&info, "/", &captures, 1, &counters);
+        
+        let rendered = render_value(
+            &json!({
+                "items": "{{request.json.items}}",
+                "total": "{{request.json.total}}",
+                "label": "n={{request.json.total}}",
+                "missing": "{{request.query.x}}"
+            }),
+            &context,
+        );
+        assert_eq!(
+            rendered,
+            json!({ "items": [{ "sku": "a" }], "total": 3, "label": "n=3", "missing": null })
+        );
+    }
+}
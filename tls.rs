@@ -0,0 +1,547 @@
+use axum::{
+    extract::Extension,
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+    routing::get,
+    Router,
+};
+use rcgen::{
+    BasicConstraints, CertificateParams, DnType, ExtendedKeyUsagePurpose, IsCa, KeyPair,
+    KeyUsagePurpose,
+};
+use rustls::{
+    client::danger::HandshakeSignatureValid,
+    crypto::{verify_tls12_signature, verify_tls13_signature, CryptoProvider},
+    pki_types::{pem::PemObject, CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer, UnixTime},
+    server::{
+        danger::{ClientCertVerified, ClientCertVerifier},
+        ServerConnection, WebPkiClientVerifier,
+    },
+    DigitallySignedStruct, DistinguishedName, ProtocolVersion, RootCertStore, ServerConfig,
+    SignatureScheme,
+};
+use serde::Serialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::{net::IpAddr, sync::Arc, time::Duration};
+use tokio::net::TcpListener;
+use tokio_rustls::TlsAcceptor;
+use x509_parser::{extensions::GeneralName, parse_x509_certificate, time::ASN1Time};
+
+use crate::config::{env_flag, env_list, env_or};
+use crate::protocol;
+use crate::AppState;
+
+// Validade do certificado gerado; clientes da Apple recusam folhas com mais de 825 dias
+const GENERATED_VALIDITY_DAYS: i64 = 365;
+
+// Handshake parado não pode prender a task (e o socket) para sempre
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Listener HTTPS opcional, ao lado (ou no lugar) do HTTP em texto puro
+#[derive(Clone)]
+pub struct TlsConfig {
+    pub enabled: bool,
+    pub port: u16,
+    pub only: bool,
+    pub client_auth: ClientAuth,
+    cert_path: Option<String>,
+    key_path: Option<String>,
+    hostnames: Vec<String>,
+    pub client_ca_path: Option<String>,
+}
+
+// Se o servidor pede certificado ao cliente durante o handshake
+#[derive(Clone, Copy, PartialEq)]
+pub enum ClientAuth {
+    Off,
+    Request,
+    Require,
+}
+
+impl ClientAuth {
+    fn from_name(name: &str) -> Self {
+        match name.trim().to_lowercase().as_str() {
+            "request" | "optional" => Self::Request,
+            "require" | "required" => Self::Require,
+            _ => Self::Off,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Request => "request",
+            Self::Require => "require",
+        }
+    }
+}
+
+impl TlsConfig {
+    pub fn from_env() -> Self {
+        let cert_path = std::env::var("RUSTJIN_TLS_CERT").ok().filter(|v| !v.trim().is_empty());
+        let key_path = std::env::var("RUSTJIN_TLS_KEY").ok().filter(|v| !v.trim().is_empty());
+        let only = env_flag("RUSTJIN_TLS_ONLY");
+        let client_ca_path = std::env::var("RUSTJIN_TLS_CLIENT_CA")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        let client_auth = ClientAuth::from_name(&env_or("RUSTJIN_TLS_CLIENT_AUTH", "off"));
+        
+        Self {
+            // HTTP/3 sempre usa TLS
+            enabled: env_flag("RUSTJIN_TLS")
+                || env_flag("RUSTJIN_H3")
+                || only
+                || cert_path.is_some()
+                || client_auth != ClientAuth::Off,
+            port: env_or("RUSTJIN_TLS_PORT", "8443").parse().unwrap_or(8443),
+            only,
+            client_auth,
+            cert_path,
+            key_path,
+            hostnames: env_list("RUSTJIN_TLS_HOSTNAMES", "localhost,127.0.0.1,::1"),
+            client_ca_path,
+        }
+    }
+}
+
+// Certificado em uso e, quando gerado na inicialização, a CA que o assinou
+#[derive(Clone)]
+pub struct TlsSetup {
+    pub server_config: Arc<ServerConfig>,
+    pub ca_pem: Option<String>,
+}
+
+impl TlsSetup {
+    pub fn load(config: &TlsConfig) -> Result<Self, String> {
+        let (chain, key, ca_pem) = match (&config.cert_path, &config.key_path) {
+            (Some(cert_path), Some(key_path)) => {
+                let (chain, key) = read_pem(cert_path, key_path)?;
+                (chain, key, None)
+            }
+            (Some(_), None) | (None, Some(_)) => {
+                return Err("RUSTJIN_TLS_CERT and RUSTJIN_TLS_KEY must be set together".to_string())
+            }
+            (None, None) => {
+                let (chain, key, ca_pem) = generate(&config.hostnames)?;
+                (chain, key, Some(ca_pem))
+            }
+        };
+        
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let builder = ServerConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()
+            .map_err(|e| e.to_string())?;
+        let builder = match config.client_auth {
+            ClientAuth::Off => builder.with_no_client_auth(),
+            mode => builder.with_client_cert_verifier(client_verifier(config, mode, provider)?),
+        };
+        let mut server_config = builder
+            .with_single_cert(chain, key)
+            .map_err(|e| format!("Invalid certificate or key: {}", e))?;
+        server_config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+        
+        Ok(Self {
+            server_config: Arc::new(server_config),
+            ca_pem,
+        })
+    }
+}
+
+// Com RUSTJIN_TLS_CLIENT_CA o certificado do cliente precisa ser emitido por essa CA; sem
+// ela qualquer certificado é aceito (só a posse da chave privada é verificada)
+fn client_verifier(
+    config: &TlsConfig,
+    mode: ClientAuth,
+    provider: Arc<CryptoProvider>,
+) -> Result<Arc<dyn ClientCertVerifier>, String> {
+    let Some(ca_path) = &config.client_ca_path else {
+        return Ok(Arc::new(AnyClientCert {
+            mandatory: mode == ClientAuth::Require,
+            provider,
+        }));
+    };
+    
+    let mut roots = RootCertStore::empty();
+    for cert in read_certs(ca_path)? {
+        roots
+            .add(cert)
+            .map_err(|e| format!("Invalid client CA in {}: {}", ca_path, e))?;
+    }
+    
+    let builder = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider);
+    let builder = match mode {
+        ClientAuth::Request => builder.allow_unauthenticated(),
+        _ => builder,
+    };
+    builder
+        .build()
+        .map_err(|e| format!("Invalid client CA in {}: {}", ca_path, e))
+}
+
+// Aceita qualquer certificado de cliente, para ecoar identidades sem configurar a CA
+#[derive(Debug)]
+struct AnyClientCert {
+    mandatory: bool,
+    provider: Arc<CryptoProvider>,
+}
+
+impl ClientCertVerifier for AnyClientCert {
+    fn client_auth_mandatory(&self) -> bool {
+        self.mandatory
+    }
+
+    fn root_hint_subjects(&self) -> &[DistinguishedName] {
+        &[]
+    }
+
+    fn verify_client_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _now: UnixTime,
+    ) -> Result<ClientCertVerified, rustls::Error> {
+        Ok(ClientCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(message, cert, dss, &self.provider.signature_verification_algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(message, cert, dss, &self.provider.signature_verification_algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+fn read_certs(path: &str) -> Result<Vec<CertificateDer<'static>>, String> {
+    let certs = CertificateDer::pem_file_iter(path)
+        .and_then(|certs| certs.collect::<Result<Vec<_>, _>>())
+        .map_err(|e| format!("Failed to read certificate {}: {}", path, e))?;
+    if certs.is_empty() {
+        return Err(format!("No certificate found in {}", path));
+    }
+    Ok(certs)
+}
+
+fn read_pem(
+    cert_path: &str,
+    key_path: &str,
+) -> Result<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>), String> {
+    let chain = read_certs(cert_path)?;
+    let key = PrivateKeyDer::from_pem_file(key_path)
+        .map_err(|e| format!("Failed to read private key {}: {}", key_path, e))?;
+    
+    Ok((chain, key))
+}
+
+// CA autoassinada + certificado do servidor para os hostnames configurados
+fn generate(
+    hostnames: &[String],
+) -> Result<(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>, String), String> {
+    let error = |e: rcgen::Error| format!("Failed to generate certificate: {}", e);
+    
+    let today = chrono::Utc::now().date_naive();
+    let date = |day: chrono::NaiveDate| {
+        use chrono::Datelike;
+        rcgen::date_time_ymd(day.year(), day.month() as u8, day.day() as u8)
+    };
+    let not_before = date(today - chrono::Duration::days(1));
+    let not_after = date(today + chrono::Duration::days(GENERATED_VALIDITY_DAYS));
+    
+    let ca_key = KeyPair::generate().map_err(error)?;
+    let mut ca_params = CertificateParams::default();
+    ca_params
+        .distinguished_name
+        .push(DnType::CommonName, "RustJin Local CA");
+    ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    ca_params.key_usages = vec![
+        KeyUsagePurpose::KeyCertSign,
+        KeyUsagePurpose::CrlSign,
+        KeyUsagePurpose::DigitalSignature,
+    ];
+    ca_params.not_before = not_before;
+    ca_params.not_after = not_after;
+    let ca_cert = ca_params.self_signed(&ca_key).map_err(error)?;
+    
+    // IPs da lista viram SANs do tipo IP automaticamente
+    let leaf_key = KeyPair::generate().map_err(error)?;
+    let mut leaf_params = CertificateParams::new(hostnames.to_vec()).map_err(error)?;
+    leaf_params
+        .distinguished_name
+        .push(DnType::CommonName, hostnames.first().map(String::as_str).unwrap_or("localhost"));
+    leaf_params.key_usages = vec![KeyUsagePurpose::DigitalSignature];
+    leaf_params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ServerAuth];
+    leaf_params.not_before = not_before;
+    leaf_params.not_after = not_after;
+    let leaf_cert = leaf_params
+        .signed_by(&leaf_key, &ca_cert, &ca_key)
+        .map_err(error)?;
+    
+    let chain = vec![leaf_cert.der().clone(), ca_cert.der().clone()];
+    let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(leaf_key.serialize_der()));
+    
+    Ok((chain, key, ca_cert.pem()))
+}
+
+// Parâmetros negociados no handshake, anexados a cada requisição da conexão
+#[derive(Clone, Serialize)]
+pub struct TlsInfo {
+    pub protocol: String,
+    pub cipher_suite: String,
+    pub alpn: Option<String>,
+    pub sni: Option<String>,
+    pub client_certificate: bool,
+    // Cadeia apresentada pelo cliente (DER), a folha primeiro
+    #[serde(skip)]
+    pub client_chain: Vec<CertificateDer<'static>>,
+}
+
+impl TlsInfo {
+    fn from_connection(connection: &ServerConnection) -> Self {
+        let protocol = match connection.protocol_version() {
+            Some(ProtocolVersion::TLSv1_3) => "TLSv1.3".to_string(),
+            Some(ProtocolVersion::TLSv1_2) => "TLSv1.2".to_string(),
+            Some(other) => format!("{:?}", other),
+            None => "unknown".to_string(),
+        };
+        let cipher_suite = connection
+            .negotiated_cipher_suite()
+            .map(|suite| format!("{:?}", suite.suite()))
+            .unwrap_or_else(|| "unknown".to_string());
+        
+        let client_chain: Vec<CertificateDer<'static>> = connection
+            .peer_certificates()
+            .map(|certs| certs.iter().map(|cert| cert.clone().into_owned()).collect())
+            .unwrap_or_default();
+        
+        Self {
+            protocol,
+            cipher_suite,
+            alpn: connection
+                .alpn_protocol()
+                .map(|alpn| String::from_utf8_lossy(alpn).to_string()),
+            sni: connection.server_name().map(str::to_string),
+            client_certificate: !client_chain.is_empty(),
+            client_chain,
+        }
+    }
+}
+
+// Aceita conexões TLS e entrega cada requisição ao mesmo Router do HTTP
+pub async fn serve(listener: TcpListener, setup: &TlsSetup, app: Router, state: Arc<AppState>) {
+    let acceptor = TlsAcceptor::from(setup.server_config.clone());
+    
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                tracing::warn!("⚠️  Falha ao aceitar conexão TLS: {}", e);
+                continue;
+            }
+        };
+        
+        let acceptor = acceptor.clone();
+        let app = app.clone();
+        let state = state.clone();
+        
+        tokio::spawn(async move {
+            let stream = match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                Ok(Ok(stream)) => stream,
+                Ok(Err(e)) => {
+                    tracing::debug!("🔒 Handshake TLS falhou ({}): {}", peer, e);
+                    return;
+                }
+                Err(_) => {
+                    tracing::debug!("🔒 Handshake TLS expirou ({})", peer);
+                    return;
+                }
+            };
+            
+            let info = TlsInfo::from_connection(stream.get_ref().1);
+            protocol::serve_connection(stream, app, state, Some(info)).await;
+        });
+    }
+}
+
+pub fn routes() -> Router<Arc<AppState>> {
+    Router::new()
+        .route("/tls/info", get(handle_tls_info))
+        .route("/tls/ca.pem", get(handle_tls_ca))
+        .route("/tls/client-cert", get(handle_tls_client_cert))
+}
+
+async fn handle_tls_info(
+    axum::extract::State(state): axum::extract::State<Arc<AppState>>,
+    info: Option<Extension<TlsInfo>>,
+) -> Response {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/tls/info".to_string());
+    
+    match info {
+        Some(Extension(info)) => {
+            state.metrics.increment_success();
+            Json(info).into_response()
+        }
+        None => {
+            state.metrics.increment_failed();
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": "Not a TLS connection",
+                    "message": "This request was received over plain HTTP",
+                    "tls_port": state.config.tls.enabled.then_some(state.config.tls.port)
+                })),
+            )
+                .into_response()
+        }
+    }
+}
+
+async fn handle_tls_ca(
+    axum::extract::State(state): axum::extract::State<Arc<AppState>>,
+) -> Response {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/tls/ca.pem".to_string());
+    
+    match state.tls.as_ref().and_then(|setup| setup.ca_pem.clone()) {
+        Some(ca_pem) => {
+            state.metrics.increment_success();
+            (
+                StatusCode::OK,
+                [("content-type", "application/x-pem-file")],
+                ca_pem,
+            )
+                .into_response()
+        }
+        None => {
+            state.metrics.increment_failed();
+            (
+                StatusCode::NOT_FOUND,
+                Json(json!({
+                    "error": "Not Found",
+                    "message": "No generated CA: TLS is disabled or uses a supplied certificate"
+                })),
+            )
+                .into_response()
+        }
+    }
+}
+
+// Campos do certificado do cliente que identificam quem está do outro lado do mTLS
+fn describe_certificate(der: &CertificateDer<'_>) -> Result<Value, String> {
+    let (_, cert) = parse_x509_certificate(der.as_ref()).map_err(|e| e.to_string())?;
+    
+    let sans: Vec<String> = cert
+        .subject_alternative_name()
+        .map_err(|e| e.to_string())?
+        .map(|extension| {
+            extension
+                .value
+                .general_names
+                .iter()
+                .map(|name| match name {
+                    GeneralName::DNSName(dns) => format!("DNS:{}", dns),
+                    GeneralName::RFC822Name(email) => format!("email:{}", email),
+                    GeneralName::URI(uri) => format!("URI:{}", uri),
+                    GeneralName::IPAddress(bytes) => format!("IP:{}", ip_address(bytes)),
+                    GeneralName::DirectoryName(name) => format!("DirName:{}", name),
+                    other => format!("{:?}", other),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    
+    let validity = cert.validity();
+    let time = |time: &ASN1Time| {
+        chrono::DateTime::from_timestamp(time.timestamp(), 0)
+            .map(|t| t.to_rfc3339())
+            .unwrap_or_default()
+    };
+    
+    Ok(json!({
+        "subject": cert.subject().to_string(),
+        "issuer": cert.issuer().to_string(),
+        "sans": sans,
+        "serial": cert.raw_serial_as_string(),
+        "not_before": time(&validity.not_before),
+        "not_after": time(&validity.not_after),
+        "currently_valid": validity.is_valid(),
+        "fingerprint_sha256": hex(&Sha256::digest(der.as_ref())),
+    }))
+}
+
+fn ip_address(bytes: &[u8]) -> String {
+    if let Ok(octets) = <[u8; 4]>::try_from(bytes) {
+        IpAddr::from(octets).to_string()
+    } else if let Ok(octets) = <[u8; 16]>::try_from(bytes) {
+        IpAddr::from(octets).to_string()
+    } else {
+        hex(bytes)
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+async fn handle_tls_client_cert(
+    axum::extract::State(state): axum::extract::State<Arc<AppState>>,
+    info: Option<Extension<TlsInfo>>,
+) -> Response {
+    state.metrics.increment_total();
+    state.metrics.record_endpoint("/tls/client-cert".to_string());
+    
+    let Some(leaf) = info.as_ref().and_then(|Extension(info)| info.client_chain.first()) else {
+        state.metrics.increment_failed();
+        return (
+            StatusCode::FORBIDDEN,
+            Json(json!({
+                "error": "Forbidden",
+                "message": "No client certificate was presented",
+                "client_auth": state.config.tls.client_auth.name()
+            })),
+        )
+            .into_response();
+    };
+    
+    match describe_certificate(leaf) {
+        Ok(mut certificate) => {
+            state.metrics.increment_success();
+            certificate["chain_length"] = json!(info.map(|Extension(info)| info.client_chain.len()));
+            // Sem RUSTJIN_TLS_CLIENT_CA a cadeia não é validada
+            certificate["verified"] = json!(state.config.tls.client_ca_path.is_some());
+            Json(certificate).into_response()
+        }
+        Err(e) => {
+            state.metrics.increment_failed();
+            (
+                StatusCode::BAD_REQUEST,
+                Json(json!({
+                    "error": "Invalid client certificate",
+                    "message": e
+                })),
+            )
+                .into_response()
+        }
+    }
+}